@@ -576,6 +576,10 @@ impl ChannelCommandSearch {
                     "LANG" => {
                         // 'LANG(<locale>)' where <locale> ∈ ISO 639-3
                         if let Some(query_lang_parsed) = QueryGenericLang::from_value(meta_value) {
+                            if let Err(err) = query_lang_parsed.support() {
+                                warn!("degraded lexing for requested language: {}", err);
+                            }
+
                             Ok((None, None, Some(query_lang_parsed)))
                         } else {
                             Err(ChannelCommandBase::make_error_invalid_meta_value(
@@ -823,6 +827,10 @@ impl ChannelCommandIngest {
                     "LANG" => {
                         // 'LANG(<locale>)' where <locale> ∈ ISO 639-3
                         if let Some(query_lang_parsed) = QueryGenericLang::from_value(meta_value) {
+                            if let Err(err) = query_lang_parsed.support() {
+                                warn!("degraded lexing for requested language: {}", err);
+                            }
+
                             Ok(Some(query_lang_parsed))
                         } else {
                             Err(ChannelCommandBase::make_error_invalid_meta_value(
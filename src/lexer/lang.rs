@@ -0,0 +1,245 @@
+// Sonic
+//
+// Fast, lightweight and schema-less search backend
+// Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+#[cfg(feature = "detector-lingua")]
+pub use lingua::{IsoCode639_3, Language};
+
+#[cfg(not(feature = "detector-lingua"))]
+use std::fmt;
+#[cfg(not(feature = "detector-lingua"))]
+use std::str::FromStr;
+
+// Notice: when the language detector is not compiled in, languages are still needed to hint \
+//   the lexer with an explicit locale; those mirror the languages of the detector, so that \
+//   languages are the same in both builds.
+#[cfg(not(feature = "detector-lingua"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Language {
+    Afrikaans,
+    Albanian,
+    Arabic,
+    Armenian,
+    Azerbaijani,
+    Basque,
+    Belarusian,
+    Bengali,
+    Bokmal,
+    Bosnian,
+    Bulgarian,
+    Catalan,
+    Chinese,
+    Croatian,
+    Czech,
+    Danish,
+    Dutch,
+    English,
+    Esperanto,
+    Estonian,
+    Finnish,
+    French,
+    Ganda,
+    Georgian,
+    German,
+    Greek,
+    Gujarati,
+    Hebrew,
+    Hindi,
+    Hungarian,
+    Icelandic,
+    Indonesian,
+    Irish,
+    Italian,
+    Japanese,
+    Kazakh,
+    Korean,
+    Latin,
+    Latvian,
+    Lithuanian,
+    Macedonian,
+    Malay,
+    Maori,
+    Marathi,
+    Mongolian,
+    Nynorsk,
+    Persian,
+    Polish,
+    Portuguese,
+    Punjabi,
+    Romanian,
+    Russian,
+    Serbian,
+    Shona,
+    Slovak,
+    Slovene,
+    Somali,
+    Sotho,
+    Spanish,
+    Swahili,
+    Swedish,
+    Tagalog,
+    Tamil,
+    Telugu,
+    Thai,
+    Tsonga,
+    Tswana,
+    Turkish,
+    Ukrainian,
+    Urdu,
+    Vietnamese,
+    Welsh,
+    Xhosa,
+    Yoruba,
+    Zulu,
+}
+
+#[cfg(not(feature = "detector-lingua"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IsoCode639_3(&'static str);
+
+#[cfg(not(feature = "detector-lingua"))]
+#[derive(Debug)]
+pub struct IsoCode639_3Error;
+
+#[cfg(not(feature = "detector-lingua"))]
+const LANGUAGES_ISO_CODES: &[(Language, &str)] = &[
+    (Language::Afrikaans, "afr"),
+    (Language::Albanian, "sqi"),
+    (Language::Arabic, "ara"),
+    (Language::Armenian, "hye"),
+    (Language::Azerbaijani, "aze"),
+    (Language::Basque, "eus"),
+    (Language::Belarusian, "bel"),
+    (Language::Bengali, "ben"),
+    (Language::Bokmal, "nob"),
+    (Language::Bosnian, "bos"),
+    (Language::Bulgarian, "bul"),
+    (Language::Catalan, "cat"),
+    (Language::Chinese, "zho"),
+    (Language::Croatian, "hrv"),
+    (Language::Czech, "ces"),
+    (Language::Danish, "dan"),
+    (Language::Dutch, "nld"),
+    (Language::English, "eng"),
+    (Language::Esperanto, "epo"),
+    (Language::Estonian, "est"),
+    (Language::Finnish, "fin"),
+    (Language::French, "fra"),
+    (Language::Ganda, "lug"),
+    (Language::Georgian, "kat"),
+    (Language::German, "deu"),
+    (Language::Greek, "ell"),
+    (Language::Gujarati, "guj"),
+    (Language::Hebrew, "heb"),
+    (Language::Hindi, "hin"),
+    (Language::Hungarian, "hun"),
+    (Language::Icelandic, "isl"),
+    (Language::Indonesian, "ind"),
+    (Language::Irish, "gle"),
+    (Language::Italian, "ita"),
+    (Language::Japanese, "jpn"),
+    (Language::Kazakh, "kaz"),
+    (Language::Korean, "kor"),
+    (Language::Latin, "lat"),
+    (Language::Latvian, "lav"),
+    (Language::Lithuanian, "lit"),
+    (Language::Macedonian, "mkd"),
+    (Language::Malay, "msa"),
+    (Language::Maori, "mri"),
+    (Language::Marathi, "mar"),
+    (Language::Mongolian, "mon"),
+    (Language::Nynorsk, "nno"),
+    (Language::Persian, "fas"),
+    (Language::Polish, "pol"),
+    (Language::Portuguese, "por"),
+    (Language::Punjabi, "pan"),
+    (Language::Romanian, "ron"),
+    (Language::Russian, "rus"),
+    (Language::Serbian, "srp"),
+    (Language::Shona, "sna"),
+    (Language::Slovak, "slk"),
+    (Language::Slovene, "slv"),
+    (Language::Somali, "som"),
+    (Language::Sotho, "sot"),
+    (Language::Spanish, "spa"),
+    (Language::Swahili, "swa"),
+    (Language::Swedish, "swe"),
+    (Language::Tagalog, "tgl"),
+    (Language::Tamil, "tam"),
+    (Language::Telugu, "tel"),
+    (Language::Thai, "tha"),
+    (Language::Tsonga, "tso"),
+    (Language::Tswana, "tsn"),
+    (Language::Turkish, "tur"),
+    (Language::Ukrainian, "ukr"),
+    (Language::Urdu, "urd"),
+    (Language::Vietnamese, "vie"),
+    (Language::Welsh, "cym"),
+    (Language::Xhosa, "xho"),
+    (Language::Yoruba, "yor"),
+    (Language::Zulu, "zul"),
+];
+
+#[cfg(not(feature = "detector-lingua"))]
+impl Language {
+    pub fn iso_code_639_3(&self) -> IsoCode639_3 {
+        LANGUAGES_ISO_CODES
+            .iter()
+            .find(|(language, _)| language == self)
+            .map(|(_, code)| IsoCode639_3(code))
+            .expect("language has no iso code")
+    }
+
+    pub fn from_iso_code_639_3(iso_code: &IsoCode639_3) -> Language {
+        LANGUAGES_ISO_CODES
+            .iter()
+            .find(|(_, code)| *code == iso_code.0)
+            .map(|(language, _)| *language)
+            .expect("iso code has no language")
+    }
+}
+
+#[cfg(not(feature = "detector-lingua"))]
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(not(feature = "detector-lingua"))]
+impl FromStr for IsoCode639_3 {
+    type Err = IsoCode639_3Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        LANGUAGES_ISO_CODES
+            .iter()
+            .find(|(_, code)| code.eq_ignore_ascii_case(value))
+            .map(|(_, code)| IsoCode639_3(code))
+            .ok_or(IsoCode639_3Error)
+    }
+}
+
+#[cfg(not(feature = "detector-lingua"))]
+impl fmt::Display for IsoCode639_3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_maps_iso_codes_to_languages() {
+        assert_eq!(
+            Language::from_iso_code_639_3(&IsoCode639_3::from_str("fra").unwrap()),
+            Language::French
+        );
+        assert_eq!(Language::Bokmal.iso_code_639_3().to_string(), "nob");
+        assert!(IsoCode639_3::from_str("xxx").is_err());
+    }
+}
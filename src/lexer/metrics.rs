@@ -0,0 +1,156 @@
+// Sonic
+//
+// Fast, lightweight and schema-less search backend
+// Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+#[cfg(test)]
+use std::cell::Cell;
+#[cfg(feature = "lexer-metrics")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub struct LexerMetrics;
+
+#[derive(Clone, Copy)]
+pub enum LexerMetricsCounter {
+    DocumentsTokenized,
+    TokensYielded,
+    StopwordsDropped,
+    DetectionCalls,
+
+    // Notice: this counter only moves for texts whose locale gets detected through a \
+    //   detection cache (see 'TokenLexerDetectionCache').
+    DetectionCacheHits,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub struct LexerMetricsSnapshot {
+    pub documents_tokenized: u64,
+    pub tokens_yielded: u64,
+    pub stopwords_dropped: u64,
+    pub detection_calls: u64,
+    pub detection_cache_hits: u64,
+}
+
+#[cfg(feature = "lexer-metrics")]
+static COUNTER_DOCUMENTS_TOKENIZED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "lexer-metrics")]
+static COUNTER_TOKENS_YIELDED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "lexer-metrics")]
+static COUNTER_STOPWORDS_DROPPED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "lexer-metrics")]
+static COUNTER_DETECTION_CALLS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "lexer-metrics")]
+static COUNTER_DETECTION_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+
+// Notice: tests also count metrics per-thread, as global counters can be incremented by \
+//   other tests running in parallel (which prevents from asserting exact counts).
+#[cfg(test)]
+thread_local! {
+    static THREAD_COUNTERS: Cell<LexerMetricsSnapshot> = Cell::new(LexerMetricsSnapshot::default());
+}
+
+pub fn metrics_snapshot() -> LexerMetricsSnapshot {
+    LexerMetrics::snapshot()
+}
+
+impl LexerMetrics {
+    #[inline]
+    pub fn count(counter: LexerMetricsCounter) {
+        // Notice: counters are only used for monitoring purposes, thus they do not need to be \
+        //   synchronized with any other memory operation (relaxed ordering is the cheapest).
+        #[cfg(feature = "lexer-metrics")]
+        Self::atomic(counter).fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(test)]
+        THREAD_COUNTERS.with(|counters| {
+            let mut snapshot = counters.get();
+
+            match counter {
+                LexerMetricsCounter::DocumentsTokenized => snapshot.documents_tokenized += 1,
+                LexerMetricsCounter::TokensYielded => snapshot.tokens_yielded += 1,
+                LexerMetricsCounter::StopwordsDropped => snapshot.stopwords_dropped += 1,
+                LexerMetricsCounter::DetectionCalls => snapshot.detection_calls += 1,
+                LexerMetricsCounter::DetectionCacheHits => snapshot.detection_cache_hits += 1,
+            }
+
+            counters.set(snapshot);
+        });
+
+        // Counting is a no-op if metrics are not compiled in
+        #[cfg(not(feature = "lexer-metrics"))]
+        let _ = counter;
+    }
+
+    #[cfg(test)]
+    pub fn thread_snapshot() -> LexerMetricsSnapshot {
+        THREAD_COUNTERS.with(Cell::get)
+    }
+
+    #[cfg(feature = "lexer-metrics")]
+    pub fn snapshot() -> LexerMetricsSnapshot {
+        let value = |counter| Self::atomic(counter).load(Ordering::Relaxed);
+
+        LexerMetricsSnapshot {
+            documents_tokenized: value(LexerMetricsCounter::DocumentsTokenized),
+            tokens_yielded: value(LexerMetricsCounter::TokensYielded),
+            stopwords_dropped: value(LexerMetricsCounter::StopwordsDropped),
+            detection_calls: value(LexerMetricsCounter::DetectionCalls),
+            detection_cache_hits: value(LexerMetricsCounter::DetectionCacheHits),
+        }
+    }
+
+    #[cfg(not(feature = "lexer-metrics"))]
+    pub fn snapshot() -> LexerMetricsSnapshot {
+        LexerMetricsSnapshot::default()
+    }
+
+    #[cfg(feature = "lexer-metrics")]
+    fn atomic(counter: LexerMetricsCounter) -> &'static AtomicU64 {
+        match counter {
+            LexerMetricsCounter::DocumentsTokenized => &COUNTER_DOCUMENTS_TOKENIZED,
+            LexerMetricsCounter::TokensYielded => &COUNTER_TOKENS_YIELDED,
+            LexerMetricsCounter::StopwordsDropped => &COUNTER_STOPWORDS_DROPPED,
+            LexerMetricsCounter::DetectionCalls => &COUNTER_DETECTION_CALLS,
+            LexerMetricsCounter::DetectionCacheHits => &COUNTER_DETECTION_CACHE_HITS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "lexer-metrics")]
+    #[test]
+    fn it_counts_metrics() {
+        let before = metrics_snapshot();
+
+        LexerMetrics::count(LexerMetricsCounter::TokensYielded);
+        LexerMetrics::count(LexerMetricsCounter::TokensYielded);
+
+        // Notice: counters are global and tests run in parallel, thus they may have been \
+        //   incremented by other tests meanwhile.
+        assert!(metrics_snapshot().tokens_yielded >= before.tokens_yielded + 2);
+    }
+
+    #[test]
+    fn it_counts_thread_metrics() {
+        let before = LexerMetrics::thread_snapshot();
+
+        LexerMetrics::count(LexerMetricsCounter::DetectionCalls);
+
+        assert_eq!(
+            LexerMetrics::thread_snapshot().detection_calls,
+            before.detection_calls + 1
+        );
+    }
+
+    #[cfg(not(feature = "lexer-metrics"))]
+    #[test]
+    fn it_does_not_count_metrics() {
+        LexerMetrics::count(LexerMetricsCounter::TokensYielded);
+
+        assert_eq!(metrics_snapshot(), LexerMetricsSnapshot::default());
+    }
+}
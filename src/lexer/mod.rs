@@ -4,7 +4,8 @@
 // Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-mod stopwords;
-
+pub mod lang;
+pub mod metrics;
 pub mod ranges;
+pub mod stopwords;
 pub mod token;
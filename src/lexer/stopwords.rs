@@ -5,119 +5,286 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 use hashbrown::HashSet;
-//use whatlang::{Lang, Script};
-use lingua::Language;
+use std::borrow::Cow;
+use std::fmt;
+use whatlang::Script;
 
+#[cfg(feature = "stopwords-fst")]
+use fst::Set as FSTSet;
+#[cfg(any(feature = "stopwords-lazy", feature = "stopwords-fst"))]
+use hashbrown::HashMap;
+#[cfg(any(feature = "stopwords-lazy", feature = "stopwords-fst"))]
+use std::sync::Arc;
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+use std::sync::Mutex;
+#[cfg(feature = "stopwords-fst")]
+use std::sync::RwLock;
+
+use super::lang::Language;
 use crate::stopwords::*;
 
 pub struct LexerStopWord;
 
+// Notice: a handle holds the stopwords of a language, which lets callers look up as many \
+//   words as needed with no lock being taken (eg. all the words of a text).
+#[derive(Clone)]
+pub struct LexerStopWords(LexerStopWordsRef);
+
+#[derive(PartialEq, Debug)]
+pub enum LexerStopWordError {
+    EmptyWord(usize),
+    NotLowercase(usize),
+}
+
+// Notice: stopwords are stored as 'Cow' strings, so that both static (built-in) and owned \
+//   (eg. runtime-provided) stopwords can be held in the same set, while still looking up \
+//   words by '&str' with no allocation.
+// Notice: the set uses the default 'hashbrown' hasher (ie. AHash), like all other sets of the \
+//   lexer, which is a lot faster than the standard library SipHash hasher on short keys (see \
+//   the 'bench_lookup_stopwords_siphash' benchmark for a comparison).
+type LexerStopWordSet = HashSet<Cow<'static, str>>;
+
+// Notice: if stopwords are stored as FSTs, the sets only exist while FSTs get built (thus \
+//   the 'stopwords-fst' feature supersedes the 'stopwords-lazy' feature).
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
+type LexerStopWordSetRef = &'static LexerStopWordSet;
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+type LexerStopWordSetRef = Arc<LexerStopWordSet>;
+
+#[cfg(not(feature = "stopwords-fst"))]
+type LexerStopWordsRef = LexerStopWordSetRef;
+#[cfg(feature = "stopwords-fst")]
+type LexerStopWordsRef = Arc<FSTSet>;
+
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+pub struct LexerStopWordCache {
+    capacity: usize,
+    state: Mutex<LexerStopWordCacheState>,
+}
+
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+#[derive(Default)]
+struct LexerStopWordCacheState {
+    clock: u64,
+    sets: HashMap<Language, (Arc<LexerStopWordSet>, u64)>,
+}
+
+// Notice: the capacity must be at least the largest number of languages that use the same \
+//   script (see 'script_langs()'), as guessing the language of a text walks through all of \
+//   them, which would otherwise evict and rebuild stopwords on every guess.
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+const STOPWORDS_CACHE_MAX_LANGUAGES: usize = 16;
+
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+lazy_static! {
+    static ref STOPWORDS_CACHE: LexerStopWordCache =
+        LexerStopWordCache::new(STOPWORDS_CACHE_MAX_LANGUAGES);
+}
+
+// Notice: FSTs get built from the stopwords of a language upon its first lookup, and are \
+//   then kept for the lifetime of the process (unlike lazily-loaded stopwords, they never \
+//   get evicted, as they are much more compact). All languages with no dedicated stopwords \
+//   list share the English FST.
+#[cfg(feature = "stopwords-fst")]
+lazy_static! {
+    static ref STOPWORDS_FST: RwLock<HashMap<Language, Arc<FSTSet>>> = RwLock::new(HashMap::new());
+}
+
+// Notice: stopwords of scripts whose languages cannot be detected are built upon their first \
+//   lookup, and are never evicted.
+#[cfg(all(
+    feature = "tokenizer-khmer",
+    feature = "stopwords-lazy",
+    not(feature = "stopwords-fst")
+))]
+lazy_static! {
+    static ref STOPWORDS_SCRIPT_KHM: LexerStopWordsRef = Arc::new(make(khm::STOPWORDS_KHM));
+}
+
+#[cfg(all(feature = "tokenizer-khmer", feature = "stopwords-fst"))]
+lazy_static! {
+    static ref STOPWORDS_SCRIPT_KHM: LexerStopWordsRef = Arc::new(make_fst(khm::STOPWORDS_KHM));
+}
+
 // Recursion group #1 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_EPO: HashSet<&'static str> = make(epo::STOPWORDS_EPO);
-    static ref STOPWORDS_ENG: HashSet<&'static str> = make(eng::STOPWORDS_ENG);
-    static ref STOPWORDS_RUS: HashSet<&'static str> = make(rus::STOPWORDS_RUS);
-    static ref STOPWORDS_CMN: HashSet<&'static str> = make(cmn::STOPWORDS_CMN);
-    static ref STOPWORDS_SPA: HashSet<&'static str> = make(spa::STOPWORDS_SPA);
-    static ref STOPWORDS_POR: HashSet<&'static str> = make(por::STOPWORDS_POR);
-    static ref STOPWORDS_ITA: HashSet<&'static str> = make(ita::STOPWORDS_ITA);
-    static ref STOPWORDS_BEN: HashSet<&'static str> = make(ben::STOPWORDS_BEN);
-    static ref STOPWORDS_FRA: HashSet<&'static str> = make(fra::STOPWORDS_FRA);
-    static ref STOPWORDS_DEU: HashSet<&'static str> = make(deu::STOPWORDS_DEU);
+    static ref STOPWORDS_EPO: LexerStopWordSet = make(epo::STOPWORDS_EPO);
+    static ref STOPWORDS_ENG: LexerStopWordSet = make(eng::STOPWORDS_ENG);
+    static ref STOPWORDS_RUS: LexerStopWordSet = make(rus::STOPWORDS_RUS);
+    static ref STOPWORDS_CMN: LexerStopWordSet = make(cmn::STOPWORDS_CMN);
+    static ref STOPWORDS_SPA: LexerStopWordSet = make(spa::STOPWORDS_SPA);
+    static ref STOPWORDS_POR: LexerStopWordSet = make(por::STOPWORDS_POR);
+    static ref STOPWORDS_ITA: LexerStopWordSet = make(ita::STOPWORDS_ITA);
+    static ref STOPWORDS_BEN: LexerStopWordSet = make(ben::STOPWORDS_BEN);
+    static ref STOPWORDS_FRA: LexerStopWordSet = make(fra::STOPWORDS_FRA);
+    static ref STOPWORDS_DEU: LexerStopWordSet = make(deu::STOPWORDS_DEU);
 }
 
 // Recursion group #2 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_UKR: HashSet<&'static str> = make(ukr::STOPWORDS_UKR);
-    static ref STOPWORDS_KAT: HashSet<&'static str> = make(kat::STOPWORDS_KAT);
-    static ref STOPWORDS_ARA: HashSet<&'static str> = make(ara::STOPWORDS_ARA);
-    static ref STOPWORDS_HIN: HashSet<&'static str> = make(hin::STOPWORDS_HIN);
-    static ref STOPWORDS_JPN: HashSet<&'static str> = make(jpn::STOPWORDS_JPN);
-    static ref STOPWORDS_HEB: HashSet<&'static str> = make(heb::STOPWORDS_HEB);
-    static ref STOPWORDS_YID: HashSet<&'static str> = make(yid::STOPWORDS_YID);
-    static ref STOPWORDS_POL: HashSet<&'static str> = make(pol::STOPWORDS_POL);
-    static ref STOPWORDS_AMH: HashSet<&'static str> = make(amh::STOPWORDS_AMH);
-    static ref STOPWORDS_JAV: HashSet<&'static str> = make(jav::STOPWORDS_JAV);
+    static ref STOPWORDS_UKR: LexerStopWordSet = make(ukr::STOPWORDS_UKR);
+    static ref STOPWORDS_KAT: LexerStopWordSet = make(kat::STOPWORDS_KAT);
+    static ref STOPWORDS_ARA: LexerStopWordSet = make(ara::STOPWORDS_ARA);
+    static ref STOPWORDS_HIN: LexerStopWordSet = make(hin::STOPWORDS_HIN);
+    static ref STOPWORDS_JPN: LexerStopWordSet = make(jpn::STOPWORDS_JPN);
+    static ref STOPWORDS_HEB: LexerStopWordSet = make(heb::STOPWORDS_HEB);
+    static ref STOPWORDS_YID: LexerStopWordSet = make(yid::STOPWORDS_YID);
+    static ref STOPWORDS_POL: LexerStopWordSet = make(pol::STOPWORDS_POL);
+    static ref STOPWORDS_AMH: LexerStopWordSet = make(amh::STOPWORDS_AMH);
+    static ref STOPWORDS_JAV: LexerStopWordSet = make(jav::STOPWORDS_JAV);
 }
 
 // Recursion group #3 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_KOR: HashSet<&'static str> = make(kor::STOPWORDS_KOR);
-    static ref STOPWORDS_NOB: HashSet<&'static str> = make(nob::STOPWORDS_NOB);
-    static ref STOPWORDS_DAN: HashSet<&'static str> = make(dan::STOPWORDS_DAN);
-    static ref STOPWORDS_SWE: HashSet<&'static str> = make(swe::STOPWORDS_SWE);
-    static ref STOPWORDS_FIN: HashSet<&'static str> = make(fin::STOPWORDS_FIN);
-    static ref STOPWORDS_TUR: HashSet<&'static str> = make(tur::STOPWORDS_TUR);
-    static ref STOPWORDS_NLD: HashSet<&'static str> = make(nld::STOPWORDS_NLD);
-    static ref STOPWORDS_HUN: HashSet<&'static str> = make(hun::STOPWORDS_HUN);
-    static ref STOPWORDS_CES: HashSet<&'static str> = make(ces::STOPWORDS_CES);
-    static ref STOPWORDS_ELL: HashSet<&'static str> = make(ell::STOPWORDS_ELL);
+    static ref STOPWORDS_KOR: LexerStopWordSet = make(kor::STOPWORDS_KOR);
+    static ref STOPWORDS_NOB: LexerStopWordSet = make(nob::STOPWORDS_NOB);
+    static ref STOPWORDS_DAN: LexerStopWordSet = make(dan::STOPWORDS_DAN);
+    static ref STOPWORDS_SWE: LexerStopWordSet = make(swe::STOPWORDS_SWE);
+    static ref STOPWORDS_FIN: LexerStopWordSet = make(fin::STOPWORDS_FIN);
+    static ref STOPWORDS_TUR: LexerStopWordSet = make(tur::STOPWORDS_TUR);
+    static ref STOPWORDS_NLD: LexerStopWordSet = make(nld::STOPWORDS_NLD);
+    static ref STOPWORDS_HUN: LexerStopWordSet = make(hun::STOPWORDS_HUN);
+    static ref STOPWORDS_CES: LexerStopWordSet = make(ces::STOPWORDS_CES);
+    static ref STOPWORDS_ELL: LexerStopWordSet = make(ell::STOPWORDS_ELL);
 }
 
 // Recursion group #4 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_BUL: HashSet<&'static str> = make(bul::STOPWORDS_BUL);
-    static ref STOPWORDS_BEL: HashSet<&'static str> = make(bel::STOPWORDS_BEL);
-    static ref STOPWORDS_MAR: HashSet<&'static str> = make(mar::STOPWORDS_MAR);
-    static ref STOPWORDS_KAN: HashSet<&'static str> = make(kan::STOPWORDS_KAN);
-    static ref STOPWORDS_RON: HashSet<&'static str> = make(ron::STOPWORDS_RON);
-    static ref STOPWORDS_SLV: HashSet<&'static str> = make(slv::STOPWORDS_SLV);
-    static ref STOPWORDS_HRV: HashSet<&'static str> = make(hrv::STOPWORDS_HRV);
-    static ref STOPWORDS_SRP: HashSet<&'static str> = make(srp::STOPWORDS_SRP);
-    static ref STOPWORDS_MKD: HashSet<&'static str> = make(mkd::STOPWORDS_MKD);
-    static ref STOPWORDS_LIT: HashSet<&'static str> = make(lit::STOPWORDS_LIT);
+    static ref STOPWORDS_BUL: LexerStopWordSet = make(bul::STOPWORDS_BUL);
+    static ref STOPWORDS_BEL: LexerStopWordSet = make(bel::STOPWORDS_BEL);
+    static ref STOPWORDS_MAR: LexerStopWordSet = make(mar::STOPWORDS_MAR);
+    static ref STOPWORDS_KAN: LexerStopWordSet = make(kan::STOPWORDS_KAN);
+    static ref STOPWORDS_RON: LexerStopWordSet = make(ron::STOPWORDS_RON);
+    static ref STOPWORDS_SLV: LexerStopWordSet = make(slv::STOPWORDS_SLV);
+    static ref STOPWORDS_HRV: LexerStopWordSet = make(hrv::STOPWORDS_HRV);
+    static ref STOPWORDS_SRP: LexerStopWordSet = make(srp::STOPWORDS_SRP);
+    static ref STOPWORDS_MKD: LexerStopWordSet = make(mkd::STOPWORDS_MKD);
+    static ref STOPWORDS_LIT: LexerStopWordSet = make(lit::STOPWORDS_LIT);
 }
 
 // Recursion group #5 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_LAV: HashSet<&'static str> = make(lav::STOPWORDS_LAV);
-    static ref STOPWORDS_EST: HashSet<&'static str> = make(est::STOPWORDS_EST);
-    static ref STOPWORDS_TAM: HashSet<&'static str> = make(tam::STOPWORDS_TAM);
-    static ref STOPWORDS_VIE: HashSet<&'static str> = make(vie::STOPWORDS_VIE);
-    static ref STOPWORDS_URD: HashSet<&'static str> = make(urd::STOPWORDS_URD);
-    static ref STOPWORDS_THA: HashSet<&'static str> = make(tha::STOPWORDS_THA);
-    static ref STOPWORDS_GUJ: HashSet<&'static str> = make(guj::STOPWORDS_GUJ);
-    static ref STOPWORDS_UZB: HashSet<&'static str> = make(uzb::STOPWORDS_UZB);
-    static ref STOPWORDS_PAN: HashSet<&'static str> = make(pan::STOPWORDS_PAN);
-    static ref STOPWORDS_AZE: HashSet<&'static str> = make(aze::STOPWORDS_AZE);
+    static ref STOPWORDS_LAV: LexerStopWordSet = make(lav::STOPWORDS_LAV);
+    static ref STOPWORDS_EST: LexerStopWordSet = make(est::STOPWORDS_EST);
+    static ref STOPWORDS_TAM: LexerStopWordSet = make(tam::STOPWORDS_TAM);
+    static ref STOPWORDS_VIE: LexerStopWordSet = make(vie::STOPWORDS_VIE);
+    static ref STOPWORDS_URD: LexerStopWordSet = make(urd::STOPWORDS_URD);
+    static ref STOPWORDS_THA: LexerStopWordSet = make(tha::STOPWORDS_THA);
+    static ref STOPWORDS_GUJ: LexerStopWordSet = make(guj::STOPWORDS_GUJ);
+    static ref STOPWORDS_UZB: LexerStopWordSet = make(uzb::STOPWORDS_UZB);
+    static ref STOPWORDS_PAN: LexerStopWordSet = make(pan::STOPWORDS_PAN);
+    static ref STOPWORDS_AZE: LexerStopWordSet = make(aze::STOPWORDS_AZE);
 }
 
 // Recursion group #6 (10 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_IND: HashSet<&'static str> = make(ind::STOPWORDS_IND);
-    static ref STOPWORDS_TEL: HashSet<&'static str> = make(tel::STOPWORDS_TEL);
-    static ref STOPWORDS_PES: HashSet<&'static str> = make(pes::STOPWORDS_PES);
-    static ref STOPWORDS_MAL: HashSet<&'static str> = make(mal::STOPWORDS_MAL);
-    static ref STOPWORDS_ORI: HashSet<&'static str> = make(ori::STOPWORDS_ORI);
-    static ref STOPWORDS_MYA: HashSet<&'static str> = make(mya::STOPWORDS_MYA);
-    static ref STOPWORDS_NEP: HashSet<&'static str> = make(nep::STOPWORDS_NEP);
-    static ref STOPWORDS_SIN: HashSet<&'static str> = make(sin::STOPWORDS_SIN);
-    static ref STOPWORDS_KHM: HashSet<&'static str> = make(khm::STOPWORDS_KHM);
-    static ref STOPWORDS_TUK: HashSet<&'static str> = make(tuk::STOPWORDS_TUK);
+    static ref STOPWORDS_IND: LexerStopWordSet = make(ind::STOPWORDS_IND);
+    static ref STOPWORDS_TEL: LexerStopWordSet = make(tel::STOPWORDS_TEL);
+    static ref STOPWORDS_PES: LexerStopWordSet = make(pes::STOPWORDS_PES);
+    static ref STOPWORDS_MAL: LexerStopWordSet = make(mal::STOPWORDS_MAL);
+    static ref STOPWORDS_ORI: LexerStopWordSet = make(ori::STOPWORDS_ORI);
+    static ref STOPWORDS_MYA: LexerStopWordSet = make(mya::STOPWORDS_MYA);
+    static ref STOPWORDS_NEP: LexerStopWordSet = make(nep::STOPWORDS_NEP);
+    static ref STOPWORDS_SIN: LexerStopWordSet = make(sin::STOPWORDS_SIN);
+    static ref STOPWORDS_KHM: LexerStopWordSet = make(khm::STOPWORDS_KHM);
+    static ref STOPWORDS_TUK: LexerStopWordSet = make(tuk::STOPWORDS_TUK);
 }
 
 // Recursion group #7 (9 items)
+#[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
 lazy_static! {
-    static ref STOPWORDS_AKA: HashSet<&'static str> = make(aka::STOPWORDS_AKA);
-    static ref STOPWORDS_ZUL: HashSet<&'static str> = make(zul::STOPWORDS_ZUL);
-    static ref STOPWORDS_SNA: HashSet<&'static str> = make(sna::STOPWORDS_SNA);
-    static ref STOPWORDS_AFR: HashSet<&'static str> = make(afr::STOPWORDS_AFR);
-    static ref STOPWORDS_LAT: HashSet<&'static str> = make(lat::STOPWORDS_LAT);
-    static ref STOPWORDS_SLK: HashSet<&'static str> = make(slk::STOPWORDS_SLK);
-    static ref STOPWORDS_CAT: HashSet<&'static str> = make(cat::STOPWORDS_CAT);
-    static ref STOPWORDS_TGL: HashSet<&'static str> = make(tgl::STOPWORDS_TGL);
-    static ref STOPWORDS_HYE: HashSet<&'static str> = make(hye::STOPWORDS_HYE);
+    static ref STOPWORDS_AKA: LexerStopWordSet = make(aka::STOPWORDS_AKA);
+    static ref STOPWORDS_ZUL: LexerStopWordSet = make(zul::STOPWORDS_ZUL);
+    static ref STOPWORDS_SNA: LexerStopWordSet = make(sna::STOPWORDS_SNA);
+    static ref STOPWORDS_AFR: LexerStopWordSet = make(afr::STOPWORDS_AFR);
+    static ref STOPWORDS_LAT: LexerStopWordSet = make(lat::STOPWORDS_LAT);
+    static ref STOPWORDS_SLK: LexerStopWordSet = make(slk::STOPWORDS_SLK);
+    static ref STOPWORDS_CAT: LexerStopWordSet = make(cat::STOPWORDS_CAT);
+    static ref STOPWORDS_TGL: LexerStopWordSet = make(tgl::STOPWORDS_TGL);
+    static ref STOPWORDS_HYE: LexerStopWordSet = make(hye::STOPWORDS_HYE);
+}
+
+fn make(words: &[&'static str]) -> LexerStopWordSet {
+    // Notice: tables get built upon first access under the lock of their static, thus a \
+    //   malformed table must not panic there (as it would poison the static); it gets \
+    //   replaced with an empty table instead, which disables stopword removal for its language.
+    make_checked(words).unwrap_or_else(|err| {
+        error!(
+            "could not build stopwords table, using an empty one: {}",
+            err
+        );
+
+        LexerStopWordSet::new()
+    })
+}
+
+fn make_checked(words: &[&'static str]) -> Result<LexerStopWordSet, LexerStopWordError> {
+    // Words must be lower-case, as they are compared against normalized words
+    for (index, word) in words.iter().enumerate() {
+        if word.is_empty() {
+            return Err(LexerStopWordError::EmptyWord(index));
+        }
+        if word.chars().any(char::is_uppercase) {
+            return Err(LexerStopWordError::NotLowercase(index));
+        }
+    }
+
+    Ok(words.iter().map(|word| Cow::Borrowed(*word)).collect())
+}
+
+#[cfg(feature = "stopwords-fst")]
+fn make_fst(words: &[&'static str]) -> FSTSet {
+    // Notice: words must be inserted in lexicographic byte order, and with no duplicates (which \
+    //   the set guarantees), otherwise building the FST fails. The set is only used while the \
+    //   FST gets built, and is dropped right after.
+    let stopwords = make(words);
+
+    let mut words = stopwords
+        .iter()
+        .map(|word| word.as_bytes())
+        .collect::<Vec<&[u8]>>();
+
+    words.sort_unstable();
+
+    FSTSet::from_iter(words).unwrap_or_else(|err| {
+        error!("could not build stopwords fst, using an empty one: {}", err);
+
+        FSTSet::from_iter(Vec::<&[u8]>::new()).expect("empty fst cannot fail to build")
+    })
 }
 
-fn make<'a>(words: &[&'a str]) -> HashSet<&'a str> {
-    words.iter().copied().collect()
+impl fmt::Display for LexerStopWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            LexerStopWordError::EmptyWord(index) => write!(f, "empty_word(at index {})", index),
+            LexerStopWordError::NotLowercase(index) => {
+                write!(f, "not_lowercase(at index {})", index)
+            }
+        }
+    }
+}
+
+impl LexerStopWords {
+    pub fn contains(&self, word: &str) -> bool {
+        self.0.contains(word)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl LexerStopWord {
     pub fn is(word: &str, locale: Option<Language>) -> bool {
         if let Some(locale) = locale {
             // Word is a stopword (given locale)
-            if Self::lang_stopwords(locale).contains(word) {
+            if Self::lang(locale).contains(word) {
                 return true;
             }
         }
@@ -126,41 +293,303 @@ impl LexerStopWord {
         false
     }
 
-    fn lang_stopwords(lang: Language) -> &'static HashSet<&'static str> {
+    #[cfg(feature = "tokenizer-khmer")]
+    pub fn script(script: Script) -> Option<LexerStopWords> {
+        // Stopwords of a script whose languages cannot be detected (ie. texts written in this \
+        //   script have no locale, though they get segmented with a dedicated tokenizer)
+        match script {
+            Script::Khmer => Some(LexerStopWords(Self::script_stopwords_khmer())),
+            _ => None,
+        }
+    }
+
+    #[cfg(all(
+        feature = "tokenizer-khmer",
+        not(any(feature = "stopwords-lazy", feature = "stopwords-fst"))
+    ))]
+    fn script_stopwords_khmer() -> LexerStopWordsRef {
+        &*STOPWORDS_KHM
+    }
+
+    #[cfg(all(
+        feature = "tokenizer-khmer",
+        any(feature = "stopwords-lazy", feature = "stopwords-fst")
+    ))]
+    fn script_stopwords_khmer() -> LexerStopWordsRef {
+        STOPWORDS_SCRIPT_KHM.clone()
+    }
+
+    #[cfg(not(feature = "stopwords-fst"))]
+    pub fn lang(lang: Language) -> LexerStopWords {
+        LexerStopWords(Self::lang_stopwords(lang))
+    }
+
+    #[cfg(feature = "stopwords-fst")]
+    pub fn lang(lang: Language) -> LexerStopWords {
+        LexerStopWords(Self::lang_stopwords_fst(lang))
+    }
+
+    pub fn is_any(word: &str, script: Script) -> bool {
+        // Word is a stopword in any language using given script (with a dedicated list)
+        Self::script_langs(script)
+            .iter()
+            .any(|script_lang| Self::is(word, Some(*script_lang)))
+    }
+
+    pub fn is_supported(lang: Language) -> bool {
+        // Language has a non-empty dedicated stopwords list? (ie. does not use the fallback)
+        Self::lang_stopwords_table(lang).is_some_and(|stopwords| !stopwords.is_empty())
+    }
+
+    pub fn matches_language(text: &str, lang: Language) -> f64 {
+        // Compute the ratio of words in text that are stopwords in given language, which is a \
+        //   cheap proxy for the confidence that text is written in this language (as a full \
+        //   language detection is much heavier)
+        // Notice: this is a simple split over whitespace, that only trims punctuation around \
+        //   words and lower-cases them if needed; this is a best-effort check.
+        let lang_stopwords = Self::lang(lang);
+
+        let (mut stopwords_count, mut words_count) = (0, 0);
+
+        for word in text.split_whitespace() {
+            let word = word.trim_matches(|character: char| !character.is_alphanumeric());
+
+            if word.is_empty() {
+                continue;
+            }
+
+            let word = if word.chars().any(char::is_uppercase) {
+                Cow::Owned(word.to_lowercase())
+            } else {
+                Cow::Borrowed(word)
+            };
+
+            if lang_stopwords.contains(word.as_ref()) {
+                stopwords_count += 1;
+            }
+
+            words_count += 1;
+        }
+
+        if words_count > 0 {
+            stopwords_count as f64 / words_count as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn guess_lang(text: &str, script: Script) -> Option<Language> {
+        debug!(
+            "guessing locale from stopwords for script: {:?} and text: {}",
+            script, text
+        );
+
+        let script_langs = Self::script_langs(script);
+
+        // Script is used by a single language? (no need to count stopwords)
+        if script_langs.len() == 1 {
+            return Some(script_langs[0]);
+        }
+
+        // Count found stop-words in text for each language
+        let (mut likely_count, mut likely_lang) = (0, None);
+
+        // Split the text and consume the iterator
+        // Notice: this may seem dirty as we allocate memory, but there may be a lot of \
+        //   'script_langs' to iterate over (plus, we need to exhaust the whole list as we \
+        //   cannot break early by design).
+        let text_split = text.split_whitespace().collect::<Vec<&str>>();
+
+        for script_lang in script_langs {
+            let lang_stopwords = Self::lang(*script_lang);
+
+            if !lang_stopwords.is_empty() {
+                // This is a simple split, that does not take into account uppercase letters and \
+                //   punctuation, as to prevent memory allocations and other heavy operations. \
+                //   Trade-offs are made as this is a best-effort check.
+                let lang_count = text_split
+                    .iter()
+                    .filter(|word| lang_stopwords.contains(**word))
+                    .count();
+
+                // Found stopwords for this locale in text?
+                if lang_count > 0 {
+                    debug!(
+                        "got {} common stopwords in guess for locale: {}",
+                        lang_count, script_lang
+                    );
+
+                    if lang_count > likely_count {
+                        likely_count = lang_count;
+                        likely_lang = Some(*script_lang);
+                    }
+                }
+            }
+        }
+
+        // Return most likely locale (if any)
+        likely_lang
+    }
+
+    fn script_langs(script: Script) -> &'static [Language] {
+        // Notice: only list languages that are both supported by the lingua crate and have \
+        //   a dedicated stopwords list, if the script is used by multiple languages.
+        match script {
+            Script::Latin => &[
+                Language::English,
+                Language::French,
+                Language::Spanish,
+                Language::Portuguese,
+                Language::Italian,
+                Language::Catalan,
+                Language::Polish,
+                Language::Danish,
+                Language::Bokmal,
+                Language::Swedish,
+                Language::Finnish,
+                Language::Turkish,
+                Language::Esperanto,
+            ],
+            Script::Cyrillic => &[Language::Russian, Language::Ukrainian, Language::Belarusian],
+            Script::Arabic => &[Language::Arabic],
+            Script::Devanagari => &[Language::Hindi],
+            Script::Hiragana | Script::Katakana => &[Language::Japanese],
+            Script::Mandarin => &[Language::Chinese],
+            Script::Hangul => &[Language::Korean],
+            Script::Hebrew => &[Language::Hebrew],
+            Script::Bengali => &[Language::Bengali],
+            Script::Greek => &[Language::Greek],
+            Script::Thai => &[Language::Thai],
+            Script::Georgian => &[Language::Georgian],
+            Script::Armenian => &[Language::Armenian],
+            Script::Gujarati => &[Language::Gujarati],
+            Script::Gurmukhi => &[Language::Punjabi],
+            Script::Tamil => &[Language::Tamil],
+            Script::Telugu => &[Language::Telugu],
+            Script::Ethiopic
+            | Script::Kannada
+            | Script::Malayalam
+            | Script::Oriya
+            | Script::Myanmar
+            | Script::Sinhala
+            | Script::Khmer => &[],
+        }
+    }
+
+    #[cfg(feature = "stopwords-fst")]
+    fn lang_stopwords_fst(lang: Language) -> Arc<FSTSet> {
+        // Fallback on English stopwords if the language has no dedicated stopwords list
+        let (lang, table) = match Self::lang_stopwords_table(lang) {
+            Some(table) => (lang, table),
+            None => (Language::English, eng::STOPWORDS_ENG),
+        };
+
+        if let Some(stopwords) = STOPWORDS_FST.read().unwrap().get(&lang) {
+            return stopwords.clone();
+        }
+
+        debug!("building stopwords fst for language: {}", lang);
+
+        // Notice: the lock is not held while building, thus the same FST may rarely be built \
+        //   twice concurrently (the last one built wins, both being identical).
+        let stopwords = Arc::new(make_fst(table));
+
+        STOPWORDS_FST
+            .write()
+            .unwrap()
+            .insert(lang, stopwords.clone());
+
+        stopwords
+    }
+
+    #[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
+    fn lang_stopwords(lang: Language) -> LexerStopWordSetRef {
+        // Fallback on English stopwords if the language has no dedicated stopwords list
+        Self::lang_stopwords_dedicated(lang).unwrap_or(&*STOPWORDS_ENG)
+    }
+
+    #[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+    fn lang_stopwords(lang: Language) -> LexerStopWordSetRef {
+        // Fallback on English stopwords if the language has no dedicated stopwords list
+        Self::lang_stopwords_dedicated(lang)
+            .or_else(|| STOPWORDS_CACHE.load(Language::English))
+            .unwrap_or_default()
+    }
+
+    #[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+    fn lang_stopwords_dedicated(lang: Language) -> Option<LexerStopWordSetRef> {
+        STOPWORDS_CACHE.load(lang)
+    }
+
+    fn lang_stopwords_table(lang: Language) -> Option<&'static [&'static str]> {
+        // Notice: this must map the same languages as 'lang_stopwords_dedicated()' does when \
+        //   stopwords are neither lazily loaded nor stored as FSTs.
+        match lang {
+            Language::Esperanto => Some(epo::STOPWORDS_EPO),
+            Language::English => Some(eng::STOPWORDS_ENG),
+            Language::Russian => Some(rus::STOPWORDS_RUS),
+            Language::Chinese => Some(cmn::STOPWORDS_CMN),
+            Language::Spanish => Some(spa::STOPWORDS_SPA),
+            Language::Portuguese => Some(por::STOPWORDS_POR),
+            Language::Italian => Some(ita::STOPWORDS_ITA),
+            Language::Bengali => Some(ben::STOPWORDS_BEN),
+            Language::French => Some(fra::STOPWORDS_FRA),
+            Language::Ukrainian => Some(ukr::STOPWORDS_UKR),
+            Language::Arabic => Some(ara::STOPWORDS_ARA),
+            Language::Hindi => Some(hin::STOPWORDS_HIN),
+            Language::Japanese => Some(jpn::STOPWORDS_JPN),
+            Language::Hebrew => Some(heb::STOPWORDS_HEB),
+            Language::Polish => Some(pol::STOPWORDS_POL),
+            Language::Korean => Some(kor::STOPWORDS_KOR),
+            Language::Bokmal => Some(nob::STOPWORDS_NOB),
+            Language::Danish => Some(dan::STOPWORDS_DAN),
+            Language::Swedish => Some(swe::STOPWORDS_SWE),
+            Language::Finnish => Some(fin::STOPWORDS_FIN),
+            Language::Turkish => Some(tur::STOPWORDS_TUR),
+            Language::Belarusian => Some(bel::STOPWORDS_BEL),
+            Language::Catalan => Some(cat::STOPWORDS_CAT),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(feature = "stopwords-lazy", feature = "stopwords-fst")))]
+    fn lang_stopwords_dedicated(lang: Language) -> Option<LexerStopWordSetRef> {
         match lang {
             // Some languages are not supported by the lingua crate
-            Language::Esperanto => &*STOPWORDS_EPO,
-            Language::English => &*STOPWORDS_ENG,
-            Language::Russian => &*STOPWORDS_RUS,
-            Language::Chinese => &*STOPWORDS_CMN,
-            Language::Spanish => &*STOPWORDS_SPA,
-            Language::Portuguese => &*STOPWORDS_POR,
-            Language::Italian => &*STOPWORDS_ITA,
-            Language::Bengali => &*STOPWORDS_BEN,
-            Language::French => &*STOPWORDS_FRA,
+            Language::Esperanto => Some(&*STOPWORDS_EPO),
+            Language::English => Some(&*STOPWORDS_ENG),
+            Language::Russian => Some(&*STOPWORDS_RUS),
+            Language::Chinese => Some(&*STOPWORDS_CMN),
+            Language::Spanish => Some(&*STOPWORDS_SPA),
+            Language::Portuguese => Some(&*STOPWORDS_POR),
+            Language::Italian => Some(&*STOPWORDS_ITA),
+            Language::Bengali => Some(&*STOPWORDS_BEN),
+            Language::French => Some(&*STOPWORDS_FRA),
             // Language::Dutch => &*STOPWORDS_DEU,
-            Language::Ukrainian => &*STOPWORDS_UKR,
+            Language::Ukrainian => Some(&*STOPWORDS_UKR),
             // Language::Kazakh => &*STOPWORDS_KAT,
-            Language::Arabic => &*STOPWORDS_ARA,
-            Language::Hindi => &*STOPWORDS_HIN,
-            Language::Japanese => &*STOPWORDS_JPN,
-            Language::Hebrew => &*STOPWORDS_HEB,
+            Language::Arabic => Some(&*STOPWORDS_ARA),
+            Language::Hindi => Some(&*STOPWORDS_HIN),
+            Language::Japanese => Some(&*STOPWORDS_JPN),
+            Language::Hebrew => Some(&*STOPWORDS_HEB),
             //Language::Yo => &*STOPWORDS_YID,
-            Language::Polish => &*STOPWORDS_POL,
+            Language::Polish => Some(&*STOPWORDS_POL),
             //Language::Am => &*STOPWORDS_AMH,
             //Language::J => &*STOPWORDS_JAV,
-            Language::Korean => &*STOPWORDS_KOR,
-            // Language::Nob => &*STOPWORDS_NOB,
-            Language::Danish => &*STOPWORDS_DAN,
-            Language::Swedish => &*STOPWORDS_SWE,
-            Language::Finnish => &*STOPWORDS_FIN,
-            Language::Turkish => &*STOPWORDS_TUR,
+            Language::Korean => Some(&*STOPWORDS_KOR),
+            // Notice: lingua has no generic Norwegian language, as it distinguishes the Bokmål and \
+            //   Nynorsk written standards. There is no dedicated Nynorsk stopwords list.
+            Language::Bokmal => Some(&*STOPWORDS_NOB),
+            Language::Danish => Some(&*STOPWORDS_DAN),
+            Language::Swedish => Some(&*STOPWORDS_SWE),
+            Language::Finnish => Some(&*STOPWORDS_FIN),
+            Language::Turkish => Some(&*STOPWORDS_TUR),
             // Language::N => &*STOPWORDS_NLD,
             // Language::Hun => &*STOPWORDS_HUN,
             // Language::Ces => &*STOPWORDS_CES,
             // Language::Ell => &*STOPWORDS_ELL,
             // Language::Bul => &*STOPWORDS_BUL,
-            // Language::Bel => &*STOPWORDS_BEL,
+            Language::Belarusian => Some(&*STOPWORDS_BEL),
             // Language::Mar => &*STOPWORDS_MAR,
             // Language::Kan => &*STOPWORDS_KAN,
             // Language::Ron => &*STOPWORDS_RON,
@@ -187,6 +616,8 @@ impl LexerStopWord {
             // Language::Mya => &*STOPWORDS_MYA,
             // Language::Nep => &*STOPWORDS_NEP,
             // Language::Sin => &*STOPWORDS_SIN,
+            // Notice: lingua has no Khmer language, thus Khmer stopwords are looked up from \
+            //   their script instead (see 'script()').
             // Language::Khm => &*STOPWORDS_KHM,
             // Language::Tuk => &*STOPWORDS_TUK,
             // Language::Aka => &*STOPWORDS_AKA,
@@ -195,11 +626,78 @@ impl LexerStopWord {
             // Language::Afr => &*STOPWORDS_AFR,
             // Language::Lat => &*STOPWORDS_LAT,
             // Language::Slk => &*STOPWORDS_SLK,
-            Language::Catalan => &*STOPWORDS_CAT,
+            Language::Catalan => Some(&*STOPWORDS_CAT),
             // Language::Tgl => &*STOPWORDS_TGL,
             // Language::Hye => &*STOPWORDS_HYE,
-            _ => &*STOPWORDS_ENG,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+impl LexerStopWordCache {
+    pub fn new(capacity: usize) -> Self {
+        LexerStopWordCache {
+            capacity,
+            state: Mutex::new(LexerStopWordCacheState::default()),
+        }
+    }
+
+    pub fn load(&self, lang: Language) -> Option<Arc<LexerStopWordSet>> {
+        // Language has no dedicated stopwords list? (nothing to load)
+        let table = LexerStopWord::lang_stopwords_table(lang)?;
+
+        let mut state = self.state.lock().unwrap();
+
+        state.clock += 1;
+
+        let clock = state.clock;
+
+        // Stopwords already loaded? (mark them as recently used)
+        if let Some((stopwords, last_used)) = state.sets.get_mut(&lang) {
+            *last_used = clock;
+
+            return Some(stopwords.clone());
         }
+
+        // Evict the least recently used stopwords? (if the cache is full)
+        // Notice: a capacity of zero means that stopwords never get evicted.
+        if self.capacity > 0 && state.sets.len() >= self.capacity {
+            let evicted_lang = state
+                .sets
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(evicted_lang, _)| *evicted_lang);
+
+            if let Some(evicted_lang) = evicted_lang {
+                debug!("evicting stopwords for language: {}", evicted_lang);
+
+                state.sets.remove(&evicted_lang);
+            }
+        }
+
+        debug!("loading stopwords for language: {}", lang);
+
+        let stopwords = Arc::new(make(table));
+
+        state.sets.insert(lang, (stopwords.clone(), clock));
+
+        Some(stopwords)
+    }
+
+    pub fn loaded(&self) -> Vec<Language> {
+        let mut langs = self
+            .state
+            .lock()
+            .unwrap()
+            .sets
+            .keys()
+            .copied()
+            .collect::<Vec<Language>>();
+
+        langs.sort();
+
+        langs
     }
 }
 
@@ -215,6 +713,168 @@ mod tests {
         assert!(!LexerStopWord::is("bonjour", Some(Language::French)));
         assert!(LexerStopWord::is("ici", Some(Language::French)));
         assert!(LexerStopWord::is("adéu", Some(Language::Catalan)));
+        assert!(!LexerStopWord::is("the", Some(Language::Belarusian)));
+        assert!(LexerStopWord::is("гэта", Some(Language::Belarusian)));
+        assert!(!LexerStopWord::is("кніга", Some(Language::Belarusian)));
+        assert!(LexerStopWord::is("jeg", Some(Language::Bokmal)));
+        assert!(!LexerStopWord::is("bok", Some(Language::Bokmal)));
+    }
+
+    #[test]
+    fn it_supports_stopwords() {
+        assert!(LexerStopWord::is_supported(Language::English));
+        assert!(LexerStopWord::is_supported(Language::French));
+        assert!(LexerStopWord::is_supported(Language::Bokmal));
+        assert!(!LexerStopWord::is_supported(Language::Nynorsk));
+        assert!(!LexerStopWord::is_supported(Language::Yoruba));
+        assert!(LexerStopWord::is_supported(Language::Belarusian));
+    }
+
+    #[test]
+    fn it_matches_language_stopwords() {
+        let text = "This is one of the best things that we have ever seen, isn't it?";
+
+        assert!(LexerStopWord::matches_language(text, Language::English) > 0.5);
+        assert!(LexerStopWord::matches_language(text, Language::French) < 0.2);
+
+        assert_eq!(LexerStopWord::matches_language("", Language::English), 0.0);
+        assert_eq!(
+            LexerStopWord::matches_language("...", Language::English),
+            0.0
+        );
+    }
+
+    #[test]
+    fn it_recovers_from_malformed_stopwords() {
+        assert_eq!(
+            make_checked(&["the", "", "of"]),
+            Err(LexerStopWordError::EmptyWord(1))
+        );
+        assert_eq!(
+            make_checked(&["the", "Of"]),
+            Err(LexerStopWordError::NotLowercase(1))
+        );
+
+        assert!(make_checked(eng::STOPWORDS_ENG).is_ok());
+
+        // Malformed tables are replaced with an empty table (ie. no stopword is removed)
+        let stopwords = make(&["the", "Of"]);
+
+        assert!(stopwords.is_empty());
+        assert!(!stopwords.contains("the"));
+    }
+
+    #[cfg(feature = "stopwords-fst")]
+    #[test]
+    fn it_detects_stopwords_fst() {
+        let stopwords = make(eng::STOPWORDS_ENG);
+        let stopwords_fst = make_fst(eng::STOPWORDS_ENG);
+
+        assert_eq!(stopwords_fst.len(), stopwords.len());
+
+        for word in eng::STOPWORDS_ENG
+            .iter()
+            .chain(["fox", "quick", "th", "thee", "", "über"].iter())
+        {
+            assert_eq!(stopwords_fst.contains(word), stopwords.contains(*word));
+        }
+
+        assert!(LexerStopWord::is("the", Some(Language::English)));
+        assert!(!LexerStopWord::is("fox", Some(Language::English)));
+        assert!(!LexerStopWord::is("the", Some(Language::Belarusian)));
+    }
+
+    #[cfg(feature = "stopwords-fst")]
+    #[test]
+    fn it_shares_fallback_stopwords_fst() {
+        let fallback = LexerStopWord::lang_stopwords_fst(Language::Yoruba);
+
+        assert!(fallback.contains("the"));
+        assert!(Arc::ptr_eq(
+            &fallback,
+            &LexerStopWord::lang_stopwords_fst(Language::Nynorsk)
+        ));
+        assert!(Arc::ptr_eq(
+            &fallback,
+            &LexerStopWord::lang_stopwords_fst(Language::English)
+        ));
+    }
+
+    #[test]
+    fn it_detects_stopwords_owned() {
+        let mut stopwords = make(&["the", "of"]);
+
+        stopwords.insert(Cow::Owned("sonic".to_string()));
+
+        assert!(stopwords.contains("the"));
+        assert!(stopwords.contains("sonic"));
+        assert!(!stopwords.contains("fox"));
+    }
+
+    #[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+    #[test]
+    fn it_loads_stopwords_lazily() {
+        let cache = LexerStopWordCache::new(2);
+
+        assert!(cache.loaded().is_empty());
+
+        assert!(cache.load(Language::French).unwrap().contains("ici"));
+        assert_eq!(cache.loaded(), vec![Language::French]);
+
+        assert!(cache.load(Language::Yoruba).is_none());
+        assert_eq!(cache.loaded(), vec![Language::French]);
+
+        cache.load(Language::English);
+        cache.load(Language::French);
+        cache.load(Language::Spanish);
+
+        // English was the least recently used language, thus it got evicted
+        assert_eq!(cache.loaded(), vec![Language::French, Language::Spanish]);
+    }
+
+    #[cfg(all(feature = "stopwords-lazy", not(feature = "stopwords-fst")))]
+    #[test]
+    fn it_caches_all_script_languages() {
+        for script in [
+            Script::Latin,
+            Script::Cyrillic,
+            Script::Arabic,
+            Script::Mandarin,
+        ] {
+            assert!(LexerStopWord::script_langs(script).len() <= STOPWORDS_CACHE_MAX_LANGUAGES);
+        }
+    }
+
+    #[test]
+    fn it_holds_lang_stopwords() {
+        let stopwords = LexerStopWord::lang(Language::English);
+
+        assert!(stopwords.contains("the"));
+        assert!(!stopwords.contains("fox"));
+        assert!(LexerStopWord::lang(Language::French).contains("ici"));
+    }
+
+    #[test]
+    fn it_guesses_language() {
+        assert_eq!(
+            LexerStopWord::guess_lang(
+                "I believe there is an extremely simple way to whip climate change.",
+                Script::Latin
+            ),
+            Some(Language::English)
+        );
+        assert_eq!(
+            LexerStopWord::guess_lang(
+                "Je crois qu'il y a une façon extrêmement simple de lutter contre le changement.",
+                Script::Latin
+            ),
+            Some(Language::French)
+        );
+        assert_eq!(
+            LexerStopWord::guess_lang("快狐跨懒狗", Script::Mandarin),
+            Some(Language::Chinese)
+        );
+        assert_eq!(LexerStopWord::guess_lang("quick fox", Script::Latin), None);
     }
 }
 
@@ -235,18 +895,51 @@ mod benches {
         b.iter(|| LexerStopWord::is("the", Some(Language::English)));
     }
 
-    // #[bench]
-    // fn bench_guess_language_latin(b: &mut Bencher) {
-    //     b.iter(|| {
-    //         LexerStopWord::guess_lang(
-    //             "I believe there is an extremely simple way to whip climate change.",
-    //             Script::Latin,
-    //         )
-    //     });
-    // }
-
-    // #[bench]
-    // fn bench_guess_language_mandarin(b: &mut Bencher) {
-    //     b.iter(|| LexerStopWord::guess_lang("快狐跨懒狗", Script::Mandarin));
-    // }
+    #[bench]
+    fn bench_lookup_stopwords_static_only(b: &mut Bencher) {
+        let stopwords: HashSet<&'static str> = eng::STOPWORDS_ENG.iter().copied().collect();
+
+        b.iter(|| stopwords.contains("the"));
+    }
+
+    #[bench]
+    fn bench_lookup_stopwords_borrowed(b: &mut Bencher) {
+        let stopwords = make(eng::STOPWORDS_ENG);
+
+        b.iter(|| stopwords.contains("the"));
+    }
+
+    #[bench]
+    fn bench_lookup_stopwords_siphash(b: &mut Bencher) {
+        let stopwords: std::collections::HashSet<Cow<'static, str>> = eng::STOPWORDS_ENG
+            .iter()
+            .map(|word| Cow::Borrowed(*word))
+            .collect();
+
+        b.iter(|| stopwords.contains("the"));
+    }
+
+    #[bench]
+    fn bench_lookup_stopwords_owned(b: &mut Bencher) {
+        let mut stopwords = make(eng::STOPWORDS_ENG);
+
+        stopwords.insert(Cow::Owned("sonic".to_string()));
+
+        b.iter(|| stopwords.contains("sonic"));
+    }
+
+    #[bench]
+    fn bench_guess_language_latin(b: &mut Bencher) {
+        b.iter(|| {
+            LexerStopWord::guess_lang(
+                "I believe there is an extremely simple way to whip climate change.",
+                Script::Latin,
+            )
+        });
+    }
+
+    #[bench]
+    fn bench_guess_language_mandarin(b: &mut Bencher) {
+        b.iter(|| LexerStopWord::guess_lang("快狐跨懒狗", Script::Mandarin));
+    }
 }
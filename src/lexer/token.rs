@@ -4,44 +4,666 @@
 // Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-use hashbrown::HashSet;
-use lingua::{Language, LanguageDetectorBuilder};
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "detector-lingua")]
+use lingua::LanguageDetectorBuilder;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt;
 //use std::time::Instant;
-use unicode_segmentation::{UnicodeSegmentation, UnicodeWords};
+use std::iter::Peekable;
+use std::mem;
+use std::ops::{Range, RangeInclusive};
+use std::option;
+use std::slice;
+use std::str;
+use std::str::SplitWhitespace;
+use std::sync::{Arc, Mutex};
+use unicode_segmentation::{UWordBoundIndices, UnicodeSegmentation, UnicodeWords};
+use whatlang::{detect_script, Script};
 
 #[cfg(feature = "tokenizer-chinese")]
 use std::vec::IntoIter;
 
-use super::stopwords::LexerStopWord;
+use super::lang::Language;
+use super::metrics::{LexerMetrics, LexerMetricsCounter};
+use super::stopwords::{LexerStopWord, LexerStopWords};
 use crate::query::types::QueryGenericLang;
 use crate::store::identifiers::{StoreTermHash, StoreTermHashed};
 
 pub struct TokenLexerBuilder;
 
+pub type TokenLexerUndetectedSink = Arc<dyn Fn(&str) + Send + Sync>;
+pub type TokenLexerAllowFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+pub type TokenLexerStemmer = Arc<dyn Fn(&str, Option<Language>) -> Option<String> + Send + Sync>;
+pub type TokenLexerFieldPolicies = HashMap<String, TokenLexerFieldPolicy>;
+
+type TokenLexerExpansion<'a> = Box<dyn FnMut(&str) -> Vec<String> + 'a>;
+
 pub struct TokenLexer<'a> {
     mode: TokenLexerMode,
     locale: Option<Language>,
+    detection: TokenLexerDetection,
+    text: &'a str,
+    blank: bool,
     words: TokenLexerWords<'a>,
-    yields: HashSet<StoreTermHashed>,
+    lookahead: VecDeque<&'a str>,
+    splits: VecDeque<&'a str>,
+    shingle_words: VecDeque<String>,
+    companions: VecDeque<(String, StoreTermHashed)>,
+    last_word_end: Option<usize>,
+    preserved: usize,
+    stopped: usize,
+    keep_stopwords: bool,
+    stopwords: Option<LexerStopWords>,
+    words_count: usize,
+    yields_count: usize,
+    yields: TokenLexerYields<'a>,
+    counts: TokenLexerCounts,
+    options: TokenLexerOptions,
+    expansion: Option<TokenLexerExpansion<'a>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TokenLexerError {
+    InvalidUtf8(usize),
+    UnsupportedLanguage(Language),
+    TokenizerFailed(Language),
 }
 
-#[derive(PartialEq)]
+#[allow(clippy::enum_variant_names)]
+#[derive(PartialEq, Clone)]
 pub enum TokenLexerMode {
     NormalizeAndCleanup(Option<Language>),
+    NormalizeAndCleanupScript(Script),
+    NormalizeAndCleanupPriority(Vec<Language>),
     NormalizeOnly,
 }
 
+pub struct TokenLexerHashes<'a> {
+    lexer: TokenLexer<'a>,
+    streamed: bool,
+}
+
+pub struct TokenLexerOffsets<'a> {
+    lexer: TokenLexer<'a>,
+}
+
+pub struct TokenLexerInterned<'a, 'i> {
+    lexer: TokenLexer<'a>,
+    interner: &'i mut TokenLexerInterner,
+}
+
+#[derive(Default)]
+pub struct TokenLexerInterner {
+    ids: HashMap<String, u32>,
+}
+
+#[derive(Default, Clone)]
+pub struct TokenLexerFieldPolicy {
+    // Tokenization policy of a record field (eg. a 'tags' field that wants whitespace \
+    //   splitting, while a 'body' field wants a locale-aware tokenization); unset values \
+    //   fall back to those used for all other fields
+    pub mode: Option<TokenLexerMode>,
+    pub tokenizer: Option<TokenLexerTokenizer>,
+    pub options: TokenLexerOptions,
+}
+
+pub struct TokenLexerDetectionCache {
+    locales: Mutex<TokenLexerDetectionCacheLocales>,
+}
+
+// Notice: locales detected with and without restricting candidates to present scripts are \
+//   cached separately, as they may differ for the same text.
+#[derive(Default)]
+struct TokenLexerDetectionCacheLocales {
+    all_scripts: HashMap<String, Option<Language>>,
+    present_scripts: HashMap<String, Option<Language>>,
+}
+
+pub struct TokenLexerChunks<'a, I: Iterator<Item = &'a str>> {
+    locale: Option<Language>,
+    sampled: VecDeque<&'a str>,
+    chunks: I,
+    carry: String,
+    words: VecDeque<(String, StoreTermHashed)>,
+    shingle_words: VecDeque<String>,
+    yields: HashSet<StoreTermHashed>,
+    yields_count: usize,
+    options: TokenLexerOptions,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct TokenLexerFootprint {
+    pub terms: usize,
+    pub term_bytes: usize,
+    pub hash_bytes: usize,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct TokenLexerDetectionReport {
+    pub samples: usize,
+    pub correct: usize,
+    pub undetected: usize,
+    pub languages: HashMap<Language, TokenLexerDetectionAccuracy>,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct TokenLexerDetectionAccuracy {
+    pub expected: usize,
+    pub detected: usize,
+    pub correct: usize,
+}
+
+pub struct TokenLexerArena {
+    buffer: String,
+    tokens: Vec<(Range<usize>, StoreTermHashed)>,
+}
+
+#[derive(Clone)]
+pub struct TokenLexerOptions {
+    pub case_mapping: TokenLexerCaseMapping,
+    pub case: TokenLexerCase,
+    pub retain_punctuation: Vec<char>,
+    pub max_word_bytes: usize,
+    pub max_chars: Option<usize>,
+    pub fold_catalan_middle_dot: bool,
+    pub chinese_fallback: TokenLexerChineseFallback,
+    pub chinese_variant: TokenLexerChineseVariant,
+    pub japanese_fallback: TokenLexerJapaneseFallback,
+    pub preserve_phrases: Vec<String>,
+    pub stopword_phrases: Vec<String>,
+    pub cjk_script_fallback: bool,
+    pub normalize_typography: bool,
+    pub max_repeats: u8,
+    pub redetect_every_words: Option<usize>,
+    pub drop_modifiers_only: bool,
+    pub split_digits: TokenLexerDigitsSplit,
+    pub detect_densest_words: Option<usize>,
+    pub split_clitics: bool,
+    pub shingle_sizes: Option<RangeInclusive<usize>>,
+    pub shingle_skip_stopwords: bool,
+    pub mixed_script_policy: Option<TokenLexerMixedScriptPolicy>,
+    pub identifier_patterns: Vec<TokenLexerIdentifierPattern>,
+    pub join_connectors: TokenLexerConnectors,
+    pub collapse_repeats: Option<usize>,
+    pub split_paths: bool,
+    pub min_word_chars: TokenLexerMinWordChars,
+    pub social_tags: bool,
+    pub detect_max_chars: usize,
+    pub mark_proper_nouns: bool,
+    pub strip_joiners: bool,
+    pub fold_ligatures: bool,
+    pub segmentation: TokenLexerSegmentation,
+    pub drop_punctuation_only: bool,
+    pub reverse_words: Option<usize>,
+    pub max_yields: Option<usize>,
+    pub strip_emoji_modifiers: bool,
+    pub fold_width: TokenLexerWidthFolding,
+    pub undetected_sink: Option<TokenLexerUndetectedSink>,
+    pub normalize_numbers: bool,
+    pub mark_undetermined: bool,
+    pub allow_filter: Option<TokenLexerAllowFilter>,
+    pub strip_controls: TokenLexerControls,
+    pub stemmer: Option<TokenLexerStemmer>,
+    pub stemming: TokenLexerStemming,
+    pub detect_present_scripts: bool,
+    pub han_only: TokenLexerHanOnly,
+    pub keep_original_case: bool,
+    pub keep_stopwords_under_words: Option<usize>,
+    pub detect_overrides: Vec<(Regex, Language)>,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerCaseMapping {
+    // Full mapping is the default, as it is the most correct one (a character may expand to \
+    //   multiple characters, eg. 'İ' maps to 'i̇')
+    #[default]
+    Full,
+
+    Simple,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerCase {
+    // Case that words are normalized to (upper-case is useful to match existing upper-case \
+    //   indexes); as this changes term hashes, the same case must be used for both pushed \
+    //   texts and queries
+    #[default]
+    Lower,
+
+    Upper,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerChineseFallback {
+    // Fallback used when the Chinese tokenizer is not compiled in (ie. the 'tokenizer-chinese' \
+    //   feature is disabled), and the text locale is Chinese
+    #[default]
+    PerCharacter,
+
+    Bigram,
+    Reject,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerChineseVariant {
+    // Script variant that Chinese words get converted to when the Chinese tokenizer is \
+    //   compiled in (ie. the 'tokenizer-chinese' feature is enabled), as to match Traditional \
+    //   and Simplified Chinese words with each other. Words are left unchanged by default.
+    // Notice: if a variant is set, text gets segmented on its Simplified form (as the \
+    //   tokenizer dictionary is made of Simplified words), then words get converted to the \
+    //   target variant.
+    #[default]
+    Unchanged,
+
+    Simplified,
+    Traditional,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerJapaneseFallback {
+    // Fallback used when the Japanese tokenizer fails to tokenize a text (ie. the \
+    //   'tokenizer-japanese' feature is enabled, and the text locale is Japanese)
+    #[default]
+    UAX29,
+
+    Reject,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerConnectors {
+    // Connector punctuation joins words as per the tokenizer in use by default; otherwise \
+    //   words are explicitly merged across the underscore (eg. 'user_id'), or across any \
+    //   connector punctuation (eg. 'user‿id')
+    #[default]
+    Tokenizer,
+
+    Underscore,
+    Punctuation,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerControls {
+    // Control characters break words as per the tokenizer in use by default; otherwise they \
+    //   are stripped from words, which get merged across them (eg. in OCR output, where \
+    //   'invo\u{0007}ice' becomes 'invoice'), optionally along with private-use-area characters
+    // Notice: common whitespace control characters (eg. tabs and line feeds) are never \
+    //   stripped, as they still separate words.
+    #[default]
+    Tokenizer,
+
+    Strip,
+    StripWithPrivateUse,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerHanOnly {
+    // Texts only written with Han characters (ie. with no kana) are inherently ambiguous \
+    //   between Chinese and Japanese, as both languages use Han characters; the detected \
+    //   language is used by default, otherwise a preferred language is forced for those \
+    //   texts (eg. if most Han-only texts of an index are known to be Japanese)
+    #[default]
+    Detected,
+
+    Chinese,
+    Japanese,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerStemming {
+    // Words are replaced with their stem by default (if a stemmer is set), which improves \
+    //   recall; otherwise the stem is yielded after the unstemmed word, so that both exact \
+    //   and stem-based queries match (eg. 'running' yields both 'running' and 'run')
+    #[default]
+    Stemmed,
+
+    StemmedAndUnstemmed,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerSegmentation {
+    // Words are segmented as per the tokenizer in use by default (ie. UAX29 word boundaries, \
+    //   which keep some intra-word punctuation, eg. 'foo.bar'); otherwise words are strictly \
+    //   made of runs of alphanumeric characters, which gives predictable words on structured \
+    //   data (eg. 'foo.bar' is split into 'foo' and 'bar')
+    #[default]
+    Tokenizer,
+
+    AlphanumericRuns,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerTokenizer {
+    // Tokenizers that words can be segmented with, which are otherwise picked depending on \
+    //   the locale (this lets a tokenizer be selected explicitly, eg. to test it in isolation)
+    UAX29,
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    HanBigrams,
+
+    #[cfg(feature = "tokenizer-whitespace")]
+    Whitespace,
+
+    #[cfg(feature = "tokenizer-chinese")]
+    JieBa,
+
+    #[cfg(feature = "tokenizer-japanese")]
+    Lindera,
+
+    #[cfg(feature = "tokenizer-khmer")]
+    Khmer,
+
+    Syllables,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerIdentifierPattern {
+    // Patterns of identifiers that are tokenized as a single word with no locale detection, \
+    //   if the whole text matches (eg. an UUID, or a hash in hexadecimal or Base64 form, of \
+    //   at least 16 and 24 characters respectively)
+    // Notice: no pattern is set by default, as identifiers would otherwise not be split \
+    //   anymore, which changes the words of already-indexed texts.
+    Uuid,
+    Hex(usize),
+    Base64(usize),
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(PartialEq, Clone, Debug)]
+pub enum TokenLexerMixedScriptPolicy {
+    // Policy used to pick the script run whose language is used as the text locale, when \
+    //   text mixes multiple scripts (eg. Latin and Han) and a single locale is needed
+    PreferLongestRun,
+    PreferFirstRun,
+    PreferConfiguredOrder(Vec<Script>),
+}
+
+#[derive(Default, PartialEq, Clone, Debug)]
+pub enum TokenLexerMinWordChars {
+    // Minimum number of characters that a word must be made of to be yielded, which depends \
+    //   on the text locale by default (single characters are meaningful in CJK languages, \
+    //   while single Latin letters are mostly noise); defaults can be overridden per-language
+    #[default]
+    Disabled,
+
+    LanguageDefaults,
+    LanguageOverrides(Vec<(Language, usize)>),
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerWidthFolding {
+    // Full-width Latin and half-width Katakana characters get folded to their normal-width \
+    //   forms, which depends on the text locale by default (Japanese text often mixes widths, \
+    //   and so do other CJK texts); folding can also be enforced or disabled for all locales
+    Disabled,
+
+    #[default]
+    LanguageDefaults,
+
+    Enabled,
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerDigitsSplit {
+    // Words are split where letters and digits meet (eg. 'abc123def' is split into 'abc', \
+    //   '123' and 'def'), which helps in searching for part numbers or SKUs
+    #[default]
+    Disabled,
+
+    Parts,
+    PartsAndWhole,
+}
+
+pub struct TokenDecision {
+    pub word: String,
+    pub normalized: Option<String>,
+    pub hash: Option<StoreTermHashed>,
+    pub outcome: TokenDecisionOutcome,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct TokenLexerCounts {
+    pub seen: usize,
+    pub unique: usize,
+    pub stopwords: usize,
+    pub duplicates: usize,
+    pub junk: usize,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TokenLexerDetection {
+    // Outcome of the locale detection, which tells apart texts whose locale could not be \
+    //   detected (ie. undetermined, 'und' in ISO 639) from texts whose locale was not detected \
+    //   in the first place (eg. if the locale was hinted, or if only normalizing)
+    NotAttempted,
+    Detected(Language),
+    Undetermined,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TokenDecisionOutcome {
+    Yielded,
+    DroppedStopword,
+    DroppedDuplicate,
+    DroppedJunk,
+}
+
 enum TokenLexerWords<'a> {
     UAX29(UnicodeWords<'a>),
+    UAX29Merged(TokenLexerMergedWords<'a>),
+    Paths(TokenLexerPathWords<'a>),
+    AlphanumericRuns(str::Split<'a, fn(char) -> bool>),
+    Syllables(str::Split<'a, fn(char) -> bool>),
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    HanBigrams(TokenLexerHanBigrams<'a>),
+
+    #[cfg(feature = "tokenizer-whitespace")]
+    Whitespace(TokenLexerWhitespaceWords<'a>),
+
+    Tokens(slice::Iter<'a, &'a str>),
+    Single(option::IntoIter<&'a str>),
 
     #[cfg(feature = "tokenizer-chinese")]
     JieBa(IntoIter<&'a str>),
 
     #[cfg(feature = "tokenizer-japanese")]
     Lindera(IntoIter<lindera_tokenizer::token::Token<'a>>),
+
+    #[cfg(feature = "tokenizer-khmer")]
+    Khmer(IntoIter<&'a str>),
+}
+
+enum TokenLexerYields<'a> {
+    Owned(HashSet<StoreTermHashed>),
+    Shared(&'a mut HashSet<StoreTermHashed>),
+    Counted(HashMap<StoreTermHashed, u8>, u8),
+    Untracked,
+}
+
+struct TokenLexerMergedWords<'a> {
+    text: &'a str,
+    bounds: Peekable<UWordBoundIndices<'a>>,
+    retain: Vec<char>,
+    prefixes: Vec<char>,
+    controls: TokenLexerControls,
+}
+
+struct TokenLexerPathWords<'a> {
+    chunks: SplitWhitespace<'a>,
+    pending: VecDeque<&'a str>,
+}
+
+#[cfg(feature = "tokenizer-whitespace")]
+struct TokenLexerWhitespaceWords<'a> {
+    words: SplitWhitespace<'a>,
+}
+
+#[cfg(not(feature = "tokenizer-chinese"))]
+struct TokenLexerHanBigrams<'a> {
+    text: &'a str,
+    bounds: Peekable<UWordBoundIndices<'a>>,
+    covered_until: usize,
 }
 
+// Sentinel value for the maximum number of times a term may be yielded, which disables \
+//   de-duplication altogether
+pub const TOKEN_REPEATS_UNLIMITED: u8 = 0;
+pub const TOKEN_PROPER_NOUN_SUFFIX: &str = "__proper";
+pub const TOKEN_UNDETERMINED_MARKER: &str = "__und";
+
 const TEXT_LANG_TRUNCATE_OVER_CHARS: usize = 200;
+const TEXT_LANG_TRUNCATE_MIN_CHARS: usize = 16;
+const TEXT_WORD_MAX_BYTES: usize = 1024;
+const TEXT_LANG_PRIORITY_CONFIDENCE_MARGIN: f64 = 0.1;
+const TEXT_LANG_DETECT_MIN_LETTERS_RATIO: f32 = 0.5;
+const TEXT_REPEATS_COLLAPSE_MIN_CHARS: usize = 3;
+const TEXT_WORD_MIN_CHARS_DEFAULT: usize = 2;
+const TEXT_WORD_MIN_CHARS_IDEOGRAPHIC: usize = 1;
+
+// Apostrophe variants that are used as an intra-word letter modifier in Ukrainian and \
+//   Belarusian (eg. 'ім’я'), which get normalized to an ASCII apostrophe
+const APOSTROPHES_CYRILLIC: &[char] = &['\u{2019}', '\u{02BC}'];
+
+// Language-specific case mappings, applied to characters before the default Unicode \
+//   lower-casing, where the Unicode mapping does not match the language rules: \
+//   - Turkish and Azerbaijani: 'I' maps to the dotless 'ı' (not 'i'), and 'İ' maps to 'i' \
+//     (not 'i̇', which is 'i' followed by a combining dot above); \
+//   - German: 'ß' has no single upper-case form, as it gets upper-cased to 'SS', and since \
+//     'SS' cannot be mapped back to 'ß' (eg. 'MASSE' is 'Masse', not 'Maße'), both 'ß' and \
+//     its rare capital 'ẞ' fold to 'ss' instead, so that 'Straße' and 'STRASSE' match.
+// Notice: Greek final sigma is handled separately, as it depends on the position of the \
+//   character within the word (see 'TokenLexerCaseMapping::fold_final_sigma()').
+const CASE_MAPPINGS_SPECIAL: &[(Language, &[(char, &str)])] = &[
+    (Language::Turkish, &[('I', "ı"), ('İ', "i")]),
+    (Language::Azerbaijani, &[('I', "ı"), ('İ', "i")]),
+    (Language::German, &[('ß', "ss"), ('ẞ', "ss")]),
+];
+
+// Invisible hyphenation marks that may be found within words (eg. soft hyphens in text \
+//   extracted from PDF or HTML documents), which get removed from words
+// Notice: soft hyphens and word joiners do not break words as per UAX29, thus words holding \
+//   those reassemble correctly once they get removed; zero-width spaces do break words as \
+//   per UAX29, though they do not with the whitespace tokenizer.
+const HYPHENS_INVISIBLE: &[char] = &['\u{00AD}', '\u{2060}', '\u{200B}'];
+const JOINER_ZERO_WIDTH: char = '\u{200D}';
+// Full-width forms of half-width Katakana characters (ie. from U+FF61 to U+FF9F, in order), \
+//   and the Katakana that compose with voiced and semi-voiced sound marks
+const KATAKANA_FULL_WIDTH: &str = concat!(
+    "。「」、・ヲァィゥェォャュョッー",
+    "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン",
+    "\u{3099}\u{309A}"
+);
+const KATAKANA_VOICED_BASES: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+const KATAKANA_SEMI_VOICED_BASES: &str = "ハヒフヘホ";
+const KATAKANA_MARK_VOICED: char = '\u{3099}';
+const KATAKANA_MARK_SEMI_VOICED: char = '\u{309A}';
+// Separators found within numbers (ie. grouping and decimal separators), whose roles depend \
+//   on the locale (eg. '1,000.5' in English is written '1.000,5' in German)
+const NUMBER_SEPARATORS: &[char] = &[',', '.', '\''];
+// Languages whose decimal separator is a point (the decimal separator is a comma for all \
+//   other languages)
+const NUMBER_DECIMAL_POINT_LANGUAGES: &[Language] = &[
+    Language::Chinese,
+    Language::English,
+    Language::Hebrew,
+    Language::Hindi,
+    Language::Irish,
+    Language::Japanese,
+    Language::Korean,
+    Language::Malay,
+    Language::Maori,
+    Language::Tagalog,
+    Language::Thai,
+    Language::Welsh,
+];
+// Variation selectors (ie. text and emoji presentation) and skin tone modifiers, that alter \
+//   how an emoji is rendered though not what it means (eg. '👍🏽' is '👍' with a skin tone)
+const MODIFIERS_EMOJI: &[char] = &[
+    '\u{FE0E}',
+    '\u{FE0F}',
+    '\u{1F3FB}',
+    '\u{1F3FC}',
+    '\u{1F3FD}',
+    '\u{1F3FE}',
+    '\u{1F3FF}',
+];
+
+// Connector punctuation characters (ie. the 'Pc' Unicode category), that can join words
+const CONNECTORS_PUNCTUATION: &[char] = &[
+    '_', '\u{203F}', '\u{2040}', '\u{2054}', '\u{FE33}', '\u{FE34}', '\u{FE4D}', '\u{FE4E}',
+    '\u{FE4F}', '\u{FF3F}',
+];
+
+// Punctuation that ends sentences, which is used to tell sentence-initial words apart
+const PUNCTUATION_SENTENCE_ENDS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+
+// Symbols that lead hashtags and mentions in social content (eg. '#RustLang' and '@user')
+const SOCIAL_TAGS_PREFIXES: &[char] = &['#', '@'];
+
+// Path separators for Unix and Windows paths (eg. '/var/log' and 'C:\\Windows')
+const PATH_SEPARATORS: &[char] = &['/', '\\'];
+
+// Apostrophe variants that attach clitics to words in Italian and Catalan (eg. 'l’uomo')
+const APOSTROPHES_CLITICS: &[char] = &['\'', '\u{2019}'];
+
+// Clitics that get elided before a word, in Italian (eg. 'dell'acqua') and Catalan \
+//   (eg. 'l'home'); and clitics that get attached after a word in Catalan (eg. 'porta'ls')
+const CLITICS_ELIDED_ITALIAN: &[&str] = &[
+    "l", "un", "dell", "dall", "nell", "sull", "all", "coll", "pell", "quell", "quest", "bell",
+    "sant", "tutt", "c", "d", "m", "n", "s", "t", "v",
+];
+const CLITICS_ELIDED_CATALAN: &[&str] = &["l", "d", "m", "n", "s", "t"];
+const CLITICS_ATTACHED_CATALAN: &[&str] = &["l", "ls", "m", "n", "ns", "s", "t"];
+
+// Middle dot variants that are typed in place of the Catalan geminated-L middle dot \
+//   (ie. 'l·l'), which get normalized to the canonical middle dot (U+00B7)
+const MIDDLE_DOTS_CATALAN: &[char] = &[
+    '\u{00B7}', '\u{2027}', '\u{0387}', '\u{22C5}', '\u{2219}', '.',
+];
+// Scripts where zero-width joiners are orthographically meaningful (ie. Indic scripts, from \
+//   Devanagari to Sinhala)
+const RANGES_INDIC: &[(char, char)] = &[('\u{0900}', '\u{0DFF}')];
+// Ranges of private-use-area characters (ie. the BMP area, and both supplementary areas), \
+//   which carry no meaning outside of the application that produced them
+const RANGES_PRIVATE_USE: &[(char, char)] = &[
+    ('\u{E000}', '\u{F8FF}'),
+    ('\u{F0000}', '\u{FFFFD}'),
+    ('\u{100000}', '\u{10FFFD}'),
+];
+// Ranges of pictographic characters (ie. emoji), that zero-width joiners combine into a \
+//   single emoji, and that are words even though they are not alphanumeric
+const RANGES_PICTOGRAPHIC: &[(char, char)] = &[
+    ('\u{2300}', '\u{23FF}'),
+    ('\u{2600}', '\u{27BF}'),
+    ('\u{2B00}', '\u{2BFF}'),
+    ('\u{1F000}', '\u{1FAFF}'),
+];
+// Ranges of scripts where syllables are delimited with a tsheg instead of words being \
+//   separated with spaces (ie. the Tibetan script, also used for Dzongkha and Ladakhi)
+const RANGES_SYLLABLE_DELIMITED: &[(char, char)] = &[('\u{0F00}', '\u{0FFF}')];
+// Ranges of syllable delimiters and punctuation of tsheg-delimited scripts (ie. head marks, \
+//   the tsheg and its non-breaking form, and the shad family of sentence delimiters)
+const RANGES_SYLLABLE_DELIMITERS: &[(char, char)] = &[('\u{0F04}', '\u{0F14}')];
+// Ranges of the Khmer script, where words are not separated with spaces
+#[cfg(feature = "tokenizer-khmer")]
+const RANGES_KHMER: &[(char, char)] = &[('\u{1780}', '\u{17FF}'), ('\u{19E0}', '\u{19FF}')];
+// Ranges of combining marks and modifier letters, that carry no meaning without a base \
+//   character (eg. orphaned diacritics)
+const RANGES_MODIFIERS: &[(char, char)] = &[
+    ('\u{02B0}', '\u{02FF}'),
+    ('\u{0300}', '\u{036F}'),
+    ('\u{1AB0}', '\u{1AFF}'),
+    ('\u{1D2C}', '\u{1D6A}'),
+    ('\u{1DC0}', '\u{1DFF}'),
+    ('\u{20D0}', '\u{20FF}'),
+    ('\u{A700}', '\u{A721}'),
+    ('\u{FE20}', '\u{FE2F}'),
+];
+
 // const TEXT_LANG_DETECT_PROCEED_OVER_CHARS: usize = 20;
 // const TEXT_LANG_DETECT_NGRAM_UNDER_CHARS: usize = 60;
 
@@ -66,393 +688,7478 @@ lazy_static! {
         .expect("unable to initialize japanese tokenizer");
 }
 
+// Notice: the Khmer segmenter cannot be shared across threads, thus each thread holds its \
+//   own (its dictionary data is compiled in, thus it is cheap to build).
+#[cfg(feature = "tokenizer-khmer")]
+thread_local! {
+    static TOKENIZER_KHMER: icu_segmenter::WordSegmenter =
+        icu_segmenter::WordSegmenter::new_dictionary();
+}
+
+pub fn normalize_word(word: &str, locale: Option<Language>) -> String {
+    // Normalize a single word, exactly as the lexer would do with default options (this is \
+    //   useful to match query terms against indexed terms, without building a whole lexer)
+    TokenLexerOptions::default().normalize_word(word, locale)
+}
+
 impl TokenLexerBuilder {
-    pub fn from(mode: TokenLexerMode, text: &str) -> Result<TokenLexer, ()> {
-        let locale = match mode {
-            TokenLexerMode::NormalizeAndCleanup(None) => {
-                // Detect text language (current lexer mode asks for a cleanup)
-                debug!("detecting locale from lexer text: {}", text);
+    pub fn from(mode: TokenLexerMode, text: &str) -> Result<TokenLexer<'_>, TokenLexerError> {
+        Self::from_with_options(mode, text, TokenLexerOptions::default())
+    }
 
-                Self::detect_lang(text)
-            }
-            TokenLexerMode::NormalizeAndCleanup(Some(lang)) => {
-                // Use hinted language (current lexer mode asks for a cleanup)
-                debug!("using hinted locale: {} from lexer text: {}", lang, text);
+    pub fn from_with_options(
+        mode: TokenLexerMode,
+        text: &str,
+        options: TokenLexerOptions,
+    ) -> Result<TokenLexer<'_>, TokenLexerError> {
+        Self::build_text(mode, text, options, None)
+    }
 
-                Some(lang)
-            }
-            TokenLexerMode::NormalizeOnly => {
-                debug!("not detecting locale from lexer text: {}", text);
+    pub fn from_with_cache<'a>(
+        mode: TokenLexerMode,
+        text: &'a str,
+        options: TokenLexerOptions,
+        cache: &TokenLexerDetectionCache,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Memoize detected locales in a caller-owned cache (eg. one cache per re-index job, \
+        //   which gets dropped along with its memory when the job ends)
+        Self::build_text(mode, text, options, Some(cache))
+    }
 
-                // May be 'NormalizeOnly' mode; no need to perform a locale detection
-                None
-            }
+    pub fn from_tokens<'a>(
+        mode: TokenLexerMode,
+        tokens: &'a [&'a str],
+        options: TokenLexerOptions,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Pass already-tokenized words straight through (eg. discrete tags), which skips both \
+        //   segmentation and locale detection. Stopwords are only removed if a locale is hinted.
+        // Notice: tokens are not slices of a single text, thus their offsets are relative to \
+        //   each token.
+        let locale = match mode {
+            TokenLexerMode::NormalizeAndCleanup(Some(lang)) => Some(lang),
+            _ => None,
         };
 
-        // Build final token builder iterator
-        Ok(TokenLexer::new(mode, text, locale))
-    }
+        let yields = Self::make_yields(&options, tokens.len() == 1);
 
-    fn detect_lang(text: &str) -> Option<Language> {
-        // Truncate text if necessary, as to avoid the ngram or stopwords detector to be \
-        //   ran on more words than those that are enough to reliably detect a locale.
-        let safe_text = if text.len() > TEXT_LANG_TRUNCATE_OVER_CHARS {
-            debug!(
-                "lexer text needs to be truncated, as it is too long ({}/{}): {}",
-                text.len(),
-                TEXT_LANG_TRUNCATE_OVER_CHARS,
-                text
-            );
+        LexerMetrics::count(LexerMetricsCounter::DocumentsTokenized);
 
-            // Perform an UTF-8 aware truncation
-            // Notice: then 'len()' check above was not UTF-8 aware, but is better than \
-            //   nothing as it avoids entering the below iterator for small strings.
-            // Notice: we fallback on text if the result is 'None'; as if it is 'None' there \
-            //   was less characters than the truncate limit in the UTF-8 parsed text. With \
-            //   this unwrap-way, we avoid doing a 'text.chars().count()' every time, which is \
-            //   a O(N) operation, and rather guard this block with a 'text.len()' which is \
-            //   a O(1) operation but which is not 100% reliable when approaching the truncate \
-            //   limit. This is a trade-off, which saves quite a lot CPU cycles at scale.
-            text.char_indices()
-                .nth(TEXT_LANG_TRUNCATE_OVER_CHARS)
-                .map(|(end_index, _)| &text[0..end_index])
-                .unwrap_or(text)
-        } else {
-            text
+        Ok(TokenLexer::with_words(
+            mode,
+            "",
+            locale,
+            TokenLexerWords::Tokens(tokens.iter()),
+            options,
+            yields,
+        ))
+    }
+
+    pub fn from_tokenizer<'a>(
+        mode: TokenLexerMode,
+        text: &'a str,
+        tokenizer: TokenLexerTokenizer,
+        options: TokenLexerOptions,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Segment words with an explicitly selected tokenizer, which skips locale detection; \
+        //   stopwords are only removed if a locale is hinted.
+        let locale = match mode {
+            TokenLexerMode::NormalizeAndCleanup(Some(lang)) => Some(lang),
+            _ => None,
         };
 
-        debug!("will detect locale for lexer safe text: {}", safe_text);
+        let words = TokenLexerWords::from_tokenizer(tokenizer, text, &options)?;
+        let yields = Self::make_yields(&options, false);
 
-        // Attempt to detect the locale from text using an hybrid method that maximizes both \
-        //   accuracy and performance.
-        // Notice: as the 'ngram' method is almost 10x slower than the 'stopwords' method, we \
-        //   prefer using the 'stopwords' method on long texts where we can be sure to see quite \
-        //   a lot of stopwords which will produce a reliable result. However, for shorter texts \
-        //   there are not enough north none stopwords, thus we use the slower 'ngram' method as \
-        //   an attempt to extract the locale using trigrams. Still, if either of these methods \
-        //   fails at detecting a locale it will try using the other method in fallback as to \
-        //   produce the most reliable result while minimizing CPU cycles.
-        let detector = LanguageDetectorBuilder::from_all_languages().build();
-        let detected_language = detector.detect_language_of(safe_text);
+        LexerMetrics::count(LexerMetricsCounter::DocumentsTokenized);
 
-        detected_language
+        Ok(TokenLexer::with_words(
+            mode, text, locale, words, options, yields,
+        ))
     }
-}
 
-impl<'a> TokenLexer<'a> {
-    fn new(mode: TokenLexerMode, text: &'a str, locale: Option<Language>) -> TokenLexer<'a> {
-        // Tokenize words (depending on the locale)
-        let words = match locale {
-            #[cfg(feature = "tokenizer-chinese")]
-            Some(Language::Chinese) => {
-                TokenLexerWords::JieBa(TOKENIZER_JIEBA.cut(text, false).into_iter())
-            }
-            #[cfg(feature = "tokenizer-japanese")]
-            Some(Language::Japanese) => match TOKENIZER_LINDERA.tokenize(text) {
-                Ok(tokens) => TokenLexerWords::Lindera(tokens.into_iter()),
-                Err(err) => {
-                    warn!("unable to tokenize japanese, falling back: {}", err);
+    pub fn from_bytes(
+        mode: TokenLexerMode,
+        bytes: &[u8],
+    ) -> Result<TokenLexer<'_>, TokenLexerError> {
+        // Validate raw bytes as UTF-8 (eg. bytes received over the network), reporting the \
+        //   position of the first invalid byte sequence on error
+        let text =
+            str::from_utf8(bytes).map_err(|err| TokenLexerError::InvalidUtf8(err.valid_up_to()))?;
 
-                    TokenLexerWords::UAX29(text.unicode_words())
-                }
-            },
-            _ => TokenLexerWords::UAX29(text.unicode_words()),
-        };
+        Self::from(mode, text)
+    }
 
-        TokenLexer {
-            mode,
-            locale,
-            words,
-            yields: HashSet::new(),
-        }
+    pub fn from_with_shared_yields<'a>(
+        mode: TokenLexerMode,
+        text: &'a str,
+        options: TokenLexerOptions,
+        yields: &'a mut HashSet<StoreTermHashed>,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Use an externally-owned set of yielded words, which makes it possible to share \
+        //   de-duplication across multiple texts (eg. to extract a vocabulary from a batch)
+        // Notice: the set is mutably borrowed for the whole lifetime of the lexer, thus the \
+        //   lexer must be dropped before the set can be passed to the next lexer.
+        Self::build(mode, text, options, TokenLexerYields::Shared(yields), None)
     }
-}
 
-impl TokenLexerMode {
-    pub fn from_query_lang(lang: Option<QueryGenericLang>) -> TokenLexerMode {
-        match lang {
-            Some(QueryGenericLang::Enabled(lang)) => {
-                // Cleanup with provided language
-                TokenLexerMode::NormalizeAndCleanup(Some(lang))
-            }
-            Some(QueryGenericLang::Disabled) => {
-                // Normalize only (language purposefully set to 'none')
-                TokenLexerMode::NormalizeOnly
-            }
-            None => {
-                // Auto-detect language and cleanup (this is the default behavior)
-                TokenLexerMode::NormalizeAndCleanup(None)
+    pub fn from_chunks<'a, I: Iterator<Item = &'a str>>(
+        mode: TokenLexerMode,
+        mut chunks: I,
+        options: TokenLexerOptions,
+    ) -> Result<TokenLexerChunks<'a, I>, TokenLexerError> {
+        // Detect locale on a sample made of the leading chunks, up to the detection limit \
+        //   (which avoids assembling the whole text); sampled chunks get lexed afterwards
+        let (mut sample, mut sampled, mut sample_chars) = (String::new(), VecDeque::new(), 0);
+
+        if mode != TokenLexerMode::NormalizeOnly {
+            while sample_chars < options.detect_max_chars {
+                match chunks.next() {
+                    Some(chunk) => {
+                        let chunk_chars =
+                            chunk.chars().take(options.detect_max_chars - sample_chars);
+
+                        sample.extend(chunk_chars.inspect(|_| sample_chars += 1));
+                        sampled.push_back(chunk);
+                    }
+                    None => break,
+                }
             }
         }
-    }
-}
 
-impl<'a> Iterator for TokenLexer<'a> {
-    type Item = (String, StoreTermHashed);
+        let locale = Self::detect_locale(&mode, &sample, &options, None)?;
 
-    // Guarantees provided by the lexer on the output: \
-    //   - Text is split per-word in a script-aware way \
-    //   - Words are normalized (ie. lower-case) \
-    //   - Gibberish words are removed (ie. words that may just be junk) \
-    //   - Stop-words are removed
-    fn next(&mut self) -> Option<Self::Item> {
-        for word in &mut self.words {
-            // Lower-case word
-            // Notice: unfortunately, as Rust is unicode-aware, we need to convert the str slice \
-            //   to a heap-indexed String; as lower-cased characters may change in bit size.
-            let word = word.to_lowercase();
-
-            // Check if normalized word is a stop-word? (if should normalize and cleanup)
-            if self.mode == TokenLexerMode::NormalizeOnly || !LexerStopWord::is(&word, self.locale)
-            {
-                // Hash the term (this is used by all iterator consumers, as well as internally \
-                //   in the iterator to keep track of already-yielded words in a space-optimized \
-                //   manner, ie. by using 32-bit unsigned integer hashes)
-                let term_hash = StoreTermHash::from(&word);
+        LexerMetrics::count(LexerMetricsCounter::DocumentsTokenized);
 
-                // Check if word was not already yielded? (we return unique words)
-                if !self.yields.contains(&term_hash) {
-                    debug!("lexer yielded word: {}", word);
+        let mut lexer = TokenLexerChunks {
+            locale,
+            sampled,
+            chunks,
+            carry: String::new(),
+            words: VecDeque::new(),
+            shingle_words: VecDeque::new(),
+            yields: HashSet::new(),
+            yields_count: 0,
+            options,
+        };
 
-                    self.yields.insert(term_hash);
+        // Tag text with the undetermined marker? (if enabled; parts of the text are lexed \
+        //   with the sampled locale hinted, thus they never get tagged on their own)
+        if mode == TokenLexerMode::NormalizeAndCleanup(None)
+            && locale.is_none()
+            && lexer.options.mark_undetermined
+        {
+            let term_hash = StoreTermHash::from(TOKEN_UNDETERMINED_MARKER);
 
-                    return Some((word, term_hash));
-                } else {
-                    debug!(
-                        "lexer did not yield word: {} because: word already yielded",
-                        word
-                    );
-                }
-            } else {
-                debug!(
-                    "lexer did not yield word: {} because: word is a stop-word",
-                    word
-                );
-            }
+            lexer.yields.insert(term_hash);
+
+            lexer
+                .words
+                .push_back((TOKEN_UNDETERMINED_MARKER.to_string(), term_hash));
         }
 
-        None
+        Ok(lexer)
     }
-}
 
-impl<'a> Iterator for TokenLexerWords<'a> {
-    type Item = &'a str;
+    pub fn from_fields<'a>(
+        mode: TokenLexerMode,
+        fields: &[&'a str],
+        options: TokenLexerOptions,
+    ) -> Result<Vec<TokenLexer<'a>>, TokenLexerError> {
+        // Detect locale once on the longest field of a record (as all fields of a record are \
+        //   most likely in the same language), then build one lexer per field with this \
+        //   locale hinted, which avoids running the detector once per field.
+        // Notice: each field lexer de-duplicates its own words, as fields are indexed apart.
+        let representative = fields
+            .iter()
+            .max_by_key(|field| field.len())
+            .copied()
+            .unwrap_or("");
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            TokenLexerWords::UAX29(token) => token.next(),
+        let locale = Self::detect_locale(&mode, representative, &options, None)?;
 
-            #[cfg(feature = "tokenizer-chinese")]
-            TokenLexerWords::JieBa(token) => token.next(),
+        fields
+            .iter()
+            .map(|field| {
+                Self::from_with_options(TokenLexerMode::from_locale(locale), field, options.clone())
+            })
+            .collect()
+    }
+
+    pub fn from_for_field<'a>(
+        mode: TokenLexerMode,
+        policies: &TokenLexerFieldPolicies,
+        field: &str,
+        text: &'a str,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Apply the tokenization policy configured for the field (if any), otherwise use \
+        //   default options with the provided mode
+        let policy = match policies.get(field) {
+            Some(policy) => policy,
+            None => return Self::from(mode, text),
+        };
+
+        let mode = policy.mode.clone().unwrap_or(mode);
+
+        match policy.tokenizer {
+            Some(tokenizer) => Self::from_tokenizer(mode, text, tokenizer, policy.options.clone()),
+            None => Self::from_with_options(mode, text, policy.options.clone()),
+        }
+    }
+
+    pub fn hash_word(word: &str) -> StoreTermHashed {
+        // Hash an already-normalized word, as the lexer hashes its yielded words (ie. the \
+        //   xxHash32 of the word UTF-8 bytes, with a seed of 0). This lets external code that \
+        //   normalizes words on its own compute hashes that are compatible with the index.
+        // Notice: the word is hashed as-is, thus it must have been normalized the same way \
+        //   the lexer would have (see 'TokenLexerOptions::normalize_word()'), otherwise its \
+        //   hash would not match that of the indexed word.
+        StoreTermHash::from(word)
+    }
+
+    fn build_text<'a>(
+        mode: TokenLexerMode,
+        text: &'a str,
+        options: TokenLexerOptions,
+        cache: Option<&TokenLexerDetectionCache>,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Skip de-duplication if the text is made of a single word (eg. a tag), as a single \
+        //   word cannot be yielded twice, and thus tracking it would be pure overhead
+        // Notice: a single word split into parts could still yield the same part twice.
+        let is_single_word =
+            options.split_digits == TokenLexerDigitsSplit::Disabled && Self::is_single_word(text);
+
+        let yields = Self::make_yields(&options, is_single_word);
+
+        Self::build(mode, text, options, yields, cache)
+    }
+
+    fn build<'a>(
+        mode: TokenLexerMode,
+        text: &'a str,
+        options: TokenLexerOptions,
+        yields: TokenLexerYields<'a>,
+        cache: Option<&TokenLexerDetectionCache>,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Only tokenize the leading part of the text? (if capped)
+        let text = if let Some(max_chars) = options.max_chars {
+            Self::window_text(text, max_chars)
+        } else {
+            text
+        };
+
+        // Text is a single identifier? (eg. an UUID, that would otherwise be split on hyphens)
+        let identifier = text.trim();
+
+        if !identifier.is_empty()
+            && options
+                .identifier_patterns
+                .iter()
+                .any(|pattern| pattern.matches(identifier))
+        {
+            debug!("not detecting locale from lexer identifier: {}", identifier);
+
+            LexerMetrics::count(LexerMetricsCounter::DocumentsTokenized);
+
+            return Ok(TokenLexer::with_words(
+                mode,
+                text,
+                None,
+                TokenLexerWords::Single(Some(identifier).into_iter()),
+                options,
+                yields,
+            ));
+        }
+
+        let locale = Self::detect_locale(&mode, text, &options, cache)?;
+
+        let detection = match (&mode, locale) {
+            (TokenLexerMode::NormalizeAndCleanup(Some(_)), _)
+            | (TokenLexerMode::NormalizeOnly, _) => TokenLexerDetection::NotAttempted,
+            (_, Some(locale)) => TokenLexerDetection::Detected(locale),
+            (_, None) => TokenLexerDetection::Undetermined,
+        };
+
+        LexerMetrics::count(LexerMetricsCounter::DocumentsTokenized);
+
+        // Build final token builder iterator
+        let mut lexer = TokenLexer::new(mode, text, locale, options, yields)?;
+
+        lexer.mark_detection(detection);
+
+        Ok(lexer)
+    }
+
+    fn detect_locale(
+        mode: &TokenLexerMode,
+        text: &str,
+        options: &TokenLexerOptions,
+        cache: Option<&TokenLexerDetectionCache>,
+    ) -> Result<Option<Language>, TokenLexerError> {
+        let locale = match mode {
+            TokenLexerMode::NormalizeAndCleanup(None) => {
+                // Detect text language (current lexer mode asks for a cleanup)
+                debug!("detecting locale from lexer text: {}", text);
+
+                let detect_text = Self::detect_text(text, options);
+
+                // Force locale from overrides? (if any matches, eg. texts with a product code \
+                //   that are known to always be English, this skips the detector entirely)
+                let locale = match (Self::detect_lang_override(detect_text, options), cache) {
+                    (Some(locale), _) => Some(locale),
+                    (None, Some(cache)) => {
+                        cache.detect(detect_text, options.detect_present_scripts)
+                    }
+                    (None, None) => Self::detect_lang(detect_text, options.detect_present_scripts),
+                };
+
+                // Guess locale from script for CJK texts that could not be detected (this \
+                //   happens on short texts), as to still use the appropriate tokenizer
+                let locale = if locale.is_none() && options.cjk_script_fallback {
+                    Self::detect_lang_cjk_script(detect_text)
+                } else {
+                    locale
+                };
+
+                // Resolve Han-only texts to the preferred language? (if any, as the tokenizer \
+                //   and stopwords both follow the locale, they always agree on the language)
+                let locale = options.han_only.resolve(locale, detect_text);
+
+                // Report text that could not be detected? (if a sink is set, eg. to learn \
+                //   which texts cannot be classified, as to improve data or configuration)
+                // Notice: the sink receives the text that detection was ran on, thus it is \
+                //   truncated if the text is long.
+                if let (None, Some(undetected_sink)) = (locale, &options.undetected_sink) {
+                    undetected_sink(detect_text);
+                }
+
+                locale
+            }
+            TokenLexerMode::NormalizeAndCleanup(Some(lang)) => {
+                // Use hinted language (current lexer mode asks for a cleanup)
+                debug!("using hinted locale: {} from lexer text: {}", lang, text);
+
+                Some(*lang)
+            }
+            TokenLexerMode::NormalizeAndCleanupScript(script) => {
+                // Guess language from hinted script (current lexer mode asks for a cleanup, \
+                //   though a full language detection is not wanted)
+                debug!(
+                    "guessing locale from hinted script: {:?} from lexer text: {}",
+                    script, text
+                );
+
+                LexerStopWord::guess_lang(text, *script)
+            }
+            TokenLexerMode::NormalizeAndCleanupPriority(langs) => {
+                // Detect text language among candidate languages, in priority order (current \
+                //   lexer mode asks for a cleanup)
+                debug!(
+                    "detecting locale among: {:?} from lexer text: {}",
+                    langs, text
+                );
+
+                Self::detect_lang_priority(Self::detect_text(text, options), langs)
+            }
+            TokenLexerMode::NormalizeOnly => {
+                debug!("not detecting locale from lexer text: {}", text);
+
+                // May be 'NormalizeOnly' mode; no need to perform a locale detection
+                None
+            }
+        };
+
+        // Reject Chinese text? (if the Chinese tokenizer is not compiled in, and the configured \
+        //   fallback does not allow for an approximate tokenization)
+        #[cfg(not(feature = "tokenizer-chinese"))]
+        {
+            if locale == Some(Language::Chinese)
+                && options.chinese_fallback == TokenLexerChineseFallback::Reject
+            {
+                warn!("rejected chinese lexer text, as no chinese tokenizer is available");
+
+                return Err(TokenLexerError::UnsupportedLanguage(Language::Chinese));
+            }
+        }
+
+        Ok(locale)
+    }
+
+    fn make_yields<'a>(options: &TokenLexerOptions, is_single_word: bool) -> TokenLexerYields<'a> {
+        match options.max_repeats {
+            1 if is_single_word => TokenLexerYields::Untracked,
+            1 => TokenLexerYields::Owned(HashSet::new()),
+            TOKEN_REPEATS_UNLIMITED => TokenLexerYields::Untracked,
+            max_repeats => TokenLexerYields::Counted(HashMap::new(), max_repeats),
+        }
+    }
+
+    fn is_single_word(text: &str) -> bool {
+        // Notice: this is a cheap check that does not require segmenting the text, which only \
+        //   catches the most common single-word texts (ie. ASCII-alphanumeric ones); such \
+        //   texts always form a single word with all tokenizers.
+        !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_alphanumeric())
+    }
+
+    fn window_text(text: &str, max_chars: usize) -> &str {
+        let mut end_index = text.len();
+        let mut chars_count = 0;
+
+        // Find the end of the last whole grapheme that fits in the window
+        // Notice: this is a grapheme-safe cut, as cutting on a character boundary could \
+        //   otherwise split a letter from its combining marks.
+        for (index, grapheme) in text.grapheme_indices(true) {
+            chars_count += grapheme.chars().count();
+
+            if chars_count > max_chars {
+                end_index = index;
+
+                break;
+            }
+        }
+
+        if end_index < text.len() {
+            debug!(
+                "lexer text needs to be windowed, as it is too long (over {} chars): {}",
+                max_chars, text
+            );
+
+            // Do not keep the last word of the window if it was cut in its middle, as it would \
+            //   otherwise yield a partial word
+            if let Some((start_index, segment)) =
+                text[..end_index].split_word_bound_indices().next_back()
+            {
+                let is_cut = text[start_index..]
+                    .split_word_bounds()
+                    .next()
+                    .is_some_and(|whole_segment| whole_segment.len() > segment.len());
+
+                if is_cut {
+                    end_index = start_index;
+                }
+            }
+        }
+
+        &text[..end_index]
+    }
+
+    fn detect_text<'t>(text: &'t str, options: &TokenLexerOptions) -> &'t str {
+        // Only detect locale on a single script run? (if text mixes scripts)
+        let text = match options.mixed_script_policy {
+            Some(ref policy) => Self::mixed_script_run(text, policy),
+            None => text,
+        };
+
+        let text = match options.detect_densest_words {
+            Some(window_words) if window_words > 0 => Self::densest_window(text, window_words),
+            _ => text,
+        };
+
+        Self::truncate_detect_text(text, options.detect_max_chars)
+    }
+
+    fn mixed_script_run<'t>(text: &'t str, policy: &TokenLexerMixedScriptPolicy) -> &'t str {
+        let runs = Self::script_runs(text);
+
+        // Text is not made of mixed scripts? (nothing to pick from)
+        if runs
+            .iter()
+            .all(|(script, _, _)| Some(script) == runs.first().map(|run| &run.0))
+        {
+            return text;
+        }
+
+        let run = match policy {
+            TokenLexerMixedScriptPolicy::PreferLongestRun => runs.iter().reduce(|longest, run| {
+                // Notice: the first run wins on ties, as to make the choice deterministic.
+                if run.2 > longest.2 {
+                    run
+                } else {
+                    longest
+                }
+            }),
+            TokenLexerMixedScriptPolicy::PreferFirstRun => runs.first(),
+            TokenLexerMixedScriptPolicy::PreferConfiguredOrder(ref scripts) => scripts
+                .iter()
+                .find_map(|script| {
+                    let script = Self::script_run_key(*script);
+
+                    runs.iter().find(|(run_script, _, _)| *run_script == script)
+                })
+                .or_else(|| runs.first()),
+        };
+
+        match run {
+            Some((script, range, _)) => {
+                debug!("picked mixed script run: {:?} for locale detection", script);
+
+                &text[range.clone()]
+            }
+            None => text,
+        }
+    }
+
+    fn script_runs(text: &str) -> Vec<(Script, Range<usize>, usize)> {
+        let mut runs: Vec<(Script, Range<usize>, usize)> = Vec::new();
+        let mut buffer = [0; 4];
+
+        // List runs of letters written in the same script, along with their letters count \
+        //   (non-letter characters within a run extend it, eg. spaces between words)
+        for (index, character) in text.char_indices() {
+            if !character.is_alphabetic() {
+                continue;
+            }
+
+            let script = match detect_script(character.encode_utf8(&mut buffer)) {
+                Some(script) => Self::script_run_key(script),
+                None => continue,
+            };
+
+            let end_index = index + character.len_utf8();
+
+            match runs.last_mut() {
+                Some((run_script, run_range, run_letters)) if *run_script == script => {
+                    run_range.end = end_index;
+
+                    *run_letters += 1;
+                }
+                _ => runs.push((script, index..end_index, 1)),
+            }
+        }
+
+        runs
+    }
+
+    fn script_run_key(script: Script) -> Script {
+        // Japanese mixes kana with Han characters within sentences, thus all those scripts \
+        //   must belong to the same run
+        match script {
+            Script::Hiragana | Script::Katakana => Script::Mandarin,
+            script => script,
+        }
+    }
+
+    fn densest_window(text: &str, window_words: usize) -> &str {
+        // Detect locale on the window of words that holds the most stopwords, rather than on \
+        //   the leading part of the text, as documents may start with non-linguistic headers \
+        //   (eg. references or jargon) that would mislead the detector
+        let script = match detect_script(text) {
+            Some(script) => script,
+            None => return text,
+        };
+
+        let words = text
+            .unicode_word_indices()
+            .map(|(index, word)| {
+                let is_stopword = LexerStopWord::is_any(
+                    &TokenLexerCaseMapping::default().lowercase(word),
+                    script,
+                );
+
+                (index, word.len(), usize::from(is_stopword))
+            })
+            .collect::<Vec<(usize, usize, usize)>>();
+
+        if words.len() <= window_words {
+            return text;
+        }
+
+        // Slide window over words, counting stopwords in the window as it goes
+        let mut window_stopwords = words[..window_words]
+            .iter()
+            .map(|(_, _, is_stopword)| is_stopword)
+            .sum::<usize>();
+
+        let (mut densest_start, mut densest_stopwords) = (0, window_stopwords);
+
+        for word_index in window_words..words.len() {
+            window_stopwords =
+                window_stopwords + words[word_index].2 - words[word_index - window_words].2;
+
+            if window_stopwords > densest_stopwords {
+                densest_start = word_index + 1 - window_words;
+                densest_stopwords = window_stopwords;
+            }
+        }
+
+        // No stopword found in text? (there is no better window than the leading one)
+        if densest_stopwords == 0 {
+            return text;
+        }
+
+        let (start_index, _, _) = words[densest_start];
+        let (end_index, end_length, _) = words[densest_start + window_words - 1];
+
+        debug!(
+            "using densest window with {} stopwords for locale detection",
+            densest_stopwords
+        );
+
+        &text[start_index..(end_index + end_length)]
+    }
+
+    fn detect_lang_cjk_script(text: &str) -> Option<Language> {
+        match detect_script(text) {
+            Some(script @ Script::Hiragana)
+            | Some(script @ Script::Katakana)
+            | Some(script @ Script::Mandarin) => {
+                debug!(
+                    "falling back on cjk script: {:?} for lexer text: {}",
+                    script, text
+                );
+
+                LexerStopWord::guess_lang(text, script)
+            }
+            _ => None,
+        }
+    }
+
+    fn detect_lang_override(safe_text: &str, options: &TokenLexerOptions) -> Option<Language> {
+        // Notice: overrides are consulted in order, thus the first matching pattern wins.
+        options
+            .detect_overrides
+            .iter()
+            .find(|(pattern, _)| pattern.is_match(safe_text))
+            .map(|(pattern, lang)| {
+                debug!(
+                    "overriding locale to: {} from pattern: {} for lexer text: {}",
+                    lang, pattern, safe_text
+                );
+
+                *lang
+            })
+    }
+
+    fn detect_lang(safe_text: &str, present_scripts: bool) -> Option<Language> {
+        // Notice: text is expected to be truncated already (see 'detect_text()').
+
+        // Skip detection on non-linguistic texts (eg. dates or punctuation), as lingua would \
+        //   otherwise assign them a meaningless language, and thus wrong stopwords
+        if !Self::is_mostly_letters(safe_text) {
+            debug!(
+                "not detecting locale from lexer text, as it has not enough letters: {}",
+                safe_text
+            );
+
+            return None;
+        }
+
+        debug!("will detect locale for lexer safe text: {}", safe_text);
+
+        // Attempt to detect the locale from text using an hybrid method that maximizes both \
+        //   accuracy and performance.
+        // Notice: as the 'ngram' method is almost 10x slower than the 'stopwords' method, we \
+        //   prefer using the 'stopwords' method on long texts where we can be sure to see quite \
+        //   a lot of stopwords which will produce a reliable result. However, for shorter texts \
+        //   there are not enough north none stopwords, thus we use the slower 'ngram' method as \
+        //   an attempt to extract the locale using trigrams. Still, if either of these methods \
+        //   fails at detecting a locale it will try using the other method in fallback as to \
+        //   produce the most reliable result while minimizing CPU cycles.
+        // Notice: if enabled, only languages written in the scripts present in the text are \
+        //   candidates, which prunes the models that the detector needs to run; texts with no \
+        //   known script are still detected among all languages.
+        if present_scripts {
+            let langs = Self::detect_lang_candidates(safe_text);
+
+            match langs.len() {
+                0 => {}
+                1 => return langs.first().copied(),
+                _ => return Self::detector_language_of_among(safe_text, &langs),
+            }
+        }
+
+        Self::detector_language_of(safe_text)
+    }
+
+    pub fn detect_lang_candidates(text: &str) -> Vec<Language> {
+        // List the languages written in the scripts that are present in the text (this is \
+        //   the candidate set that detection is restricted to, if enabled)
+        let mut langs: Vec<Language> = Vec::new();
+
+        for (script, _, _) in Self::script_runs(text) {
+            for lang in Self::script_detect_langs(script) {
+                if !langs.contains(lang) {
+                    langs.push(*lang);
+                }
+            }
+        }
+
+        langs
+    }
+
+    fn script_detect_langs(script: Script) -> &'static [Language] {
+        // Notice: this lists all languages that the detector supports for each script (unlike \
+        //   the stopwords script languages, which only list languages with stopwords). Han \
+        //   characters are used in both Chinese and Japanese, and kana are merged with them.
+        match script {
+            Script::Latin => &[
+                Language::Afrikaans,
+                Language::Albanian,
+                Language::Azerbaijani,
+                Language::Basque,
+                Language::Bokmal,
+                Language::Bosnian,
+                Language::Catalan,
+                Language::Croatian,
+                Language::Czech,
+                Language::Danish,
+                Language::Dutch,
+                Language::English,
+                Language::Esperanto,
+                Language::Estonian,
+                Language::Finnish,
+                Language::French,
+                Language::Ganda,
+                Language::German,
+                Language::Hungarian,
+                Language::Icelandic,
+                Language::Indonesian,
+                Language::Irish,
+                Language::Italian,
+                Language::Latin,
+                Language::Latvian,
+                Language::Lithuanian,
+                Language::Malay,
+                Language::Maori,
+                Language::Nynorsk,
+                Language::Polish,
+                Language::Portuguese,
+                Language::Romanian,
+                Language::Shona,
+                Language::Slovak,
+                Language::Slovene,
+                Language::Somali,
+                Language::Sotho,
+                Language::Spanish,
+                Language::Swahili,
+                Language::Swedish,
+                Language::Tagalog,
+                Language::Tsonga,
+                Language::Tswana,
+                Language::Turkish,
+                Language::Vietnamese,
+                Language::Welsh,
+                Language::Xhosa,
+                Language::Yoruba,
+                Language::Zulu,
+            ],
+            Script::Cyrillic => &[
+                Language::Belarusian,
+                Language::Bulgarian,
+                Language::Kazakh,
+                Language::Macedonian,
+                Language::Mongolian,
+                Language::Russian,
+                Language::Serbian,
+                Language::Ukrainian,
+            ],
+            Script::Arabic => &[Language::Arabic, Language::Persian, Language::Urdu],
+            Script::Devanagari => &[Language::Hindi, Language::Marathi],
+            Script::Mandarin | Script::Hiragana | Script::Katakana => {
+                &[Language::Chinese, Language::Japanese]
+            }
+            Script::Hangul => &[Language::Korean],
+            Script::Hebrew => &[Language::Hebrew],
+            Script::Bengali => &[Language::Bengali],
+            Script::Greek => &[Language::Greek],
+            Script::Thai => &[Language::Thai],
+            Script::Georgian => &[Language::Georgian],
+            Script::Armenian => &[Language::Armenian],
+            Script::Gujarati => &[Language::Gujarati],
+            Script::Gurmukhi => &[Language::Punjabi],
+            Script::Tamil => &[Language::Tamil],
+            Script::Telugu => &[Language::Telugu],
+            _ => &[],
+        }
+    }
+
+    pub fn detect_lang_report(text: &str) -> Vec<(Language, f64)> {
+        // Compute the confidence of all languages for text, as to help in tuning detection \
+        //   thresholds (this is a diagnostic helper, that is not used to lex texts)
+        let safe_text = Self::truncate_detect_text(text, TEXT_LANG_TRUNCATE_OVER_CHARS);
+
+        let mut confidences = Self::detector_all_confidence_values(safe_text);
+
+        confidences.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+        confidences
+    }
+
+    pub fn evaluate_detection(
+        samples: &[(&str, Language)],
+        options: &TokenLexerOptions,
+    ) -> TokenLexerDetectionReport {
+        // Measure how accurately texts labeled with their expected language are detected, \
+        //   under given options (this is a diagnostic helper, meant to tune detection against \
+        //   a labeled corpus, that is not used to lex texts)
+        // Notice: texts are detected the same way as they are when lexed, thus a text that \
+        //   fails to be detected is counted as undetected, and not against any language.
+        let mut report = TokenLexerDetectionReport::default();
+
+        for (text, expected) in samples {
+            let detected = Self::detect_locale(
+                &TokenLexerMode::NormalizeAndCleanup(None),
+                text,
+                options,
+                None,
+            )
+            .ok()
+            .flatten();
+
+            report.samples += 1;
+            report.languages.entry(*expected).or_default().expected += 1;
+
+            match detected {
+                Some(detected) => {
+                    let accuracy = report.languages.entry(detected).or_default();
+
+                    accuracy.detected += 1;
+
+                    if detected == *expected {
+                        accuracy.correct += 1;
+                        report.correct += 1;
+                    }
+                }
+                None => report.undetected += 1,
+            }
+        }
+
+        report
+    }
+
+    fn detect_lang_priority(safe_text: &str, langs: &[Language]) -> Option<Language> {
+        // Not enough candidate languages to run a detection? (the detector requires at least \
+        //   2 languages to choose from)
+        if langs.len() < 2 {
+            return langs.first().copied();
+        }
+
+        debug!(
+            "will detect locale among: {:?} for lexer safe text: {}",
+            langs, safe_text
+        );
+
+        let confidences = Self::detector_confidence_values(safe_text, langs);
+
+        let max_confidence = confidences
+            .iter()
+            .map(|(_, confidence)| *confidence)
+            .fold(0.0, f64::max);
+
+        // Pick the first language in priority order whose confidence is close enough to the \
+        //   best confidence (this makes the detection deterministic on ambiguous texts)
+        langs.iter().copied().find(|lang| {
+            confidences.iter().any(|(confidence_lang, confidence)| {
+                confidence_lang == lang
+                    && max_confidence - confidence <= TEXT_LANG_PRIORITY_CONFIDENCE_MARGIN
+            })
+        })
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    fn detector_language_of(text: &str) -> Option<Language> {
+        LexerMetrics::count(LexerMetricsCounter::DetectionCalls);
+
+        LanguageDetectorBuilder::from_all_languages()
+            .build()
+            .detect_language_of(text)
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    fn detector_language_of_among(text: &str, langs: &[Language]) -> Option<Language> {
+        LexerMetrics::count(LexerMetricsCounter::DetectionCalls);
+
+        LanguageDetectorBuilder::from_languages(langs)
+            .build()
+            .detect_language_of(text)
+    }
+
+    #[cfg(not(feature = "detector-lingua"))]
+    fn detector_language_of_among(text: &str, langs: &[Language]) -> Option<Language> {
+        debug!(
+            "not detecting locale among: {:?}, as no detector is compiled in: {}",
+            langs, text
+        );
+
+        None
+    }
+
+    #[cfg(not(feature = "detector-lingua"))]
+    fn detector_language_of(text: &str) -> Option<Language> {
+        // No language detector is compiled in, thus text is left with no locale (which means \
+        //   that words only get normalized, unless a locale is hinted)
+        debug!(
+            "not detecting locale, as no detector is compiled in: {}",
+            text
+        );
+
+        None
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    fn detector_confidence_values(text: &str, langs: &[Language]) -> Vec<(Language, f64)> {
+        LexerMetrics::count(LexerMetricsCounter::DetectionCalls);
+
+        LanguageDetectorBuilder::from_languages(langs)
+            .build()
+            .compute_language_confidence_values(text)
+    }
+
+    #[cfg(not(feature = "detector-lingua"))]
+    fn detector_confidence_values(text: &str, langs: &[Language]) -> Vec<(Language, f64)> {
+        debug!(
+            "not detecting locale among: {:?}, as no detector is compiled in: {}",
+            langs, text
+        );
+
+        Vec::new()
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    fn detector_all_confidence_values(text: &str) -> Vec<(Language, f64)> {
+        LexerMetrics::count(LexerMetricsCounter::DetectionCalls);
+
+        LanguageDetectorBuilder::from_all_languages()
+            .build()
+            .compute_language_confidence_values(text)
+    }
+
+    #[cfg(not(feature = "detector-lingua"))]
+    fn detector_all_confidence_values(text: &str) -> Vec<(Language, f64)> {
+        debug!(
+            "not computing locale confidences, as no detector is compiled in: {}",
+            text
+        );
+
+        Vec::new()
+    }
+
+    fn is_mostly_letters(text: &str) -> bool {
+        let (mut letters_count, mut total_count) = (0, 0);
+
+        // Notice: whitespace is not accounted for, as it is neutral to the ratio
+        for character in text.chars().filter(|character| !character.is_whitespace()) {
+            if character.is_alphabetic() {
+                letters_count += 1;
+            }
+
+            total_count += 1;
+        }
+
+        letters_count as f32 >= total_count as f32 * TEXT_LANG_DETECT_MIN_LETTERS_RATIO
+            && letters_count > 0
+    }
+
+    fn truncate_detect_text(text: &str, max_chars: usize) -> &str {
+        // Truncate text if necessary, as to avoid the ngram or stopwords detector to be \
+        //   ran on more words than those that are enough to reliably detect a locale.
+        // Notice: leading whitespace is skipped and the limit cannot go under a minimum, which \
+        //   guarantees that the truncated text is never empty nor whitespace-only (unless the \
+        //   text is blank itself), as detection would otherwise be meaningless.
+        let text = text.trim_start();
+        let max_chars = max_chars.max(TEXT_LANG_TRUNCATE_MIN_CHARS);
+
+        if text.len() > max_chars {
+            debug!(
+                "lexer text needs to be truncated, as it is too long ({}/{}): {}",
+                text.len(),
+                max_chars,
+                text
+            );
+
+            // Perform an UTF-8 aware truncation
+            // Notice: then 'len()' check above was not UTF-8 aware, but is better than \
+            //   nothing as it avoids entering the below iterator for small strings.
+            // Notice: we fallback on text if the result is 'None'; as if it is 'None' there \
+            //   was less characters than the truncate limit in the UTF-8 parsed text. With \
+            //   this unwrap-way, we avoid doing a 'text.chars().count()' every time, which is \
+            //   a O(N) operation, and rather guard this block with a 'text.len()' which is \
+            //   a O(1) operation but which is not 100% reliable when approaching the truncate \
+            //   limit. This is a trade-off, which saves quite a lot CPU cycles at scale.
+            text.char_indices()
+                .nth(max_chars)
+                .map(|(end_index, _)| &text[0..end_index])
+                .unwrap_or(text)
+        } else {
+            text
+        }
+    }
+}
+
+impl<'a> TokenLexer<'a> {
+    fn new(
+        mode: TokenLexerMode,
+        text: &'a str,
+        locale: Option<Language>,
+        options: TokenLexerOptions,
+        yields: TokenLexerYields<'a>,
+    ) -> Result<TokenLexer<'a>, TokenLexerError> {
+        // Tokenize words (depending on the locale)
+        let words = match locale {
+            #[cfg(feature = "tokenizer-chinese")]
+            Some(Language::Chinese) => {
+                TokenLexerWords::from_tokenizer(TokenLexerTokenizer::JieBa, text, &options)?
+            }
+            #[cfg(feature = "tokenizer-japanese")]
+            Some(Language::Japanese) => {
+                TokenLexerWords::from_tokenizer(TokenLexerTokenizer::Lindera, text, &options)?
+            }
+            #[cfg(not(feature = "tokenizer-chinese"))]
+            Some(Language::Chinese)
+                if options.chinese_fallback == TokenLexerChineseFallback::Bigram =>
+            {
+                TokenLexerWords::from_tokenizer(TokenLexerTokenizer::HanBigrams, text, &options)?
+            }
+            _ if options.split_paths => TokenLexerWords::Paths(TokenLexerPathWords::new(text)),
+            _ if options.segmentation == TokenLexerSegmentation::AlphanumericRuns => {
+                TokenLexerWords::AlphanumericRuns(text.split(Self::is_run_separator))
+            }
+            // Notice: texts written in scripts that delimit syllables with a tsheg (ie. the \
+            //   Tibetan script) cannot be detected, thus only undetected texts are checked.
+            None if Self::is_syllable_delimited(text) => {
+                TokenLexerWords::from_tokenizer(TokenLexerTokenizer::Syllables, text, &options)?
+            }
+            // Notice: Khmer cannot be detected either (lingua has no Khmer language), thus \
+            //   only undetected texts are checked.
+            #[cfg(feature = "tokenizer-khmer")]
+            None if Self::is_khmer(text) => {
+                TokenLexerWords::from_tokenizer(TokenLexerTokenizer::Khmer, text, &options)?
+            }
+            _ if !options.retain_punctuation.is_empty()
+                || options.join_connectors != TokenLexerConnectors::Tokenizer
+                || options.social_tags
+                || options.strip_controls != TokenLexerControls::Tokenizer =>
+            {
+                TokenLexerWords::UAX29Merged(TokenLexerMergedWords::new(
+                    text,
+                    options.merged_characters(),
+                    options.merged_prefixes(),
+                    options.strip_controls,
+                ))
+            }
+            #[cfg(feature = "tokenizer-whitespace")]
+            _ => TokenLexerWords::from_tokenizer(TokenLexerTokenizer::Whitespace, text, &options)?,
+            #[cfg(not(feature = "tokenizer-whitespace"))]
+            _ => TokenLexerWords::from_tokenizer(TokenLexerTokenizer::UAX29, text, &options)?,
+        };
+
+        Ok(Self::with_words(mode, text, locale, words, options, yields))
+    }
+
+    fn with_words(
+        mode: TokenLexerMode,
+        text: &'a str,
+        locale: Option<Language>,
+        words: TokenLexerWords<'a>,
+        options: TokenLexerOptions,
+        yields: TokenLexerYields<'a>,
+    ) -> TokenLexer<'a> {
+        // Check if input is blank before it gets tokenized (ie. empty or whitespace-only), as \
+        //   to tell it apart from an input that yields no word once filtered
+        let blank = match &words {
+            TokenLexerWords::Tokens(tokens) => tokens.clone().all(|token| token.trim().is_empty()),
+            _ => text.trim().is_empty(),
+        };
+
+        let stopwords = Self::locale_stopwords(&mode, locale, &words);
+
+        let mut lexer = TokenLexer {
+            mode,
+            locale,
+            detection: TokenLexerDetection::NotAttempted,
+            text,
+            blank,
+            words,
+            lookahead: VecDeque::new(),
+            splits: VecDeque::new(),
+            shingle_words: VecDeque::new(),
+            companions: VecDeque::new(),
+            last_word_end: None,
+            preserved: 0,
+            stopped: 0,
+            keep_stopwords: false,
+            stopwords,
+            words_count: 0,
+            yields_count: 0,
+            yields,
+            counts: TokenLexerCounts::default(),
+            options,
+            expansion: None,
+        };
+
+        // Keep stopwords if text is short? (if enabled, eg. a title or a tag, that may be \
+        //   entirely made of stopwords, and that would be emptied if they were removed)
+        // Notice: the leading words of the text are looked ahead to count them, thus no more \
+        //   words than the threshold get buffered.
+        if let Some(under_words) = lexer.options.keep_stopwords_under_words {
+            while lexer.lookahead.len() < under_words {
+                match lexer.words.next() {
+                    Some(word) => lexer.lookahead.push_back(word),
+                    None => break,
+                }
+            }
+
+            lexer.keep_stopwords = lexer.lookahead.len() < under_words;
+        }
+
+        lexer
+    }
+}
+
+impl<'a> TokenLexer<'a> {
+    pub fn explain(mut self) -> Vec<TokenDecision> {
+        let mut decisions = Vec::new();
+
+        // Run all words through the same decisions as the iterator does, but record the \
+        //   outcome of each word instead of skipping non-yielded words
+        // Notice: words that get rejected before they are normalized (eg. over-sized words) \
+        //   have no normalized form, and thus no hash.
+        while let Some(word) = self.next_word() {
+            let (normalized, term_hash, outcome) = self.decide_raw(word);
+
+            decisions.push(TokenDecision {
+                word: word.to_string(),
+                hash: term_hash.or_else(|| normalized.as_deref().map(StoreTermHash::from)),
+                normalized: normalized.map(Cow::into_owned),
+                outcome,
+            });
+        }
+
+        decisions
+    }
+
+    pub fn collect_with_stopwords(mut self) -> (Vec<(String, StoreTermHashed)>, Vec<String>) {
+        // Collect yielded words, along with the stopwords that were seen in the text (in the \
+        //   order they appear in), instead of discarding them (eg. to detect question words)
+        // Notice: a stopword is recorded every time it is seen, as to be able to reconstruct \
+        //   phrases; only words are collected (ie. shingles and other companions are not).
+        let (mut words, mut stopwords) = (Vec::new(), Vec::new());
+
+        while let Some(word) = self.next_word() {
+            match self.decide(word) {
+                (normalized, Some(term_hash), TokenDecisionOutcome::Yielded) => {
+                    words.push((normalized.into_owned(), term_hash))
+                }
+                (normalized, _, TokenDecisionOutcome::DroppedStopword) => {
+                    stopwords.push(normalized.into_owned())
+                }
+                _ => {}
+            }
+        }
+
+        (words, stopwords)
+    }
+
+    pub fn collect_by_frequency(mut self) -> Vec<(String, StoreTermHashed, u32)> {
+        // Collect yielded words along with how many times they were seen in the text, sorted \
+        //   by frequency (eg. to build a quick 'top terms' view of a document)
+        // Notice: words seen as many times are kept in the order they first appear in, as the \
+        //   sort is stable. Only words are counted (ie. shingles and other companions are not).
+        let mut words: Vec<(String, StoreTermHashed, u32)> = Vec::new();
+        let mut positions: HashMap<StoreTermHashed, usize> = HashMap::new();
+
+        while let Some(word) = self.next_word() {
+            match self.decide(word) {
+                (normalized, Some(term_hash), outcome)
+                    if outcome == TokenDecisionOutcome::Yielded
+                        || outcome == TokenDecisionOutcome::DroppedDuplicate =>
+                {
+                    // Notice: a word may be yielded more than once if repeats are allowed, \
+                    //   so always count it against its first row. A duplicate that was never \
+                    //   seen here was yielded by another lexer sharing yields, and is not \
+                    //   counted.
+                    if let Some(position) = positions.get(&term_hash) {
+                        words[*position].2 += 1;
+                    } else if outcome == TokenDecisionOutcome::Yielded {
+                        positions.insert(term_hash, words.len());
+                        words.push((normalized.into_owned(), term_hash, 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        words.sort_by_key(|word| Reverse(word.2));
+
+        words
+    }
+
+    pub fn segments(self) -> Vec<String> {
+        // Return words exactly as the tokenizer segmented them, before any normalization, \
+        //   stopword removal or de-duplication (eg. to compare tokenizations when upgrading \
+        //   a tokenizer or its dictionary)
+        // Notice: words are not split on digits, and locale is not re-detected, as those \
+        //   happen after segmentation.
+        self.lookahead
+            .into_iter()
+            .chain(self.words)
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn hashes_only(self) -> TokenLexerHashes<'a> {
+        // Yield term hashes only, which avoids allocating an owned word for each yielded word \
+        //   (words that are already normalized do not need to be copied to get hashed)
+        // Notice: words can only be streamed if the options normalize them by lower-casing \
+        //   them only, and if no companion is yielded along with words (eg. shingles or \
+        //   expanded words), which is known once for all words.
+        let streamed = self.mode == TokenLexerMode::NormalizeOnly
+            && self.options.is_streamable()
+            && self.expansion.is_none();
+
+        TokenLexerHashes {
+            lexer: self,
+            streamed,
+        }
+    }
+
+    pub fn collect_into(mut self, buffer: &mut Vec<(String, StoreTermHashed)>) {
+        // Write all yielded words into a caller-provided buffer, which gets cleared first (this \
+        //   lets hot indexing loops reuse the same buffer across texts, along with the words \
+        //   that it holds, instead of allocating new ones for each text)
+        // Notice: only words are collected (ie. shingles and other companions are not).
+        let mut filled = 0;
+
+        while let Some(word) = self.next_word() {
+            if let (normalized, Some(term_hash), TokenDecisionOutcome::Yielded) = self.decide(word)
+            {
+                match buffer.get_mut(filled) {
+                    Some((buffer_word, buffer_hash)) => {
+                        buffer_word.clear();
+                        buffer_word.push_str(&normalized);
+
+                        *buffer_hash = term_hash;
+                    }
+                    None => buffer.push((normalized.into_owned(), term_hash)),
+                }
+
+                filled += 1;
+            }
+        }
+
+        buffer.truncate(filled);
+    }
+
+    pub fn collect_arena(mut self) -> TokenLexerArena {
+        // Write all yielded words into a single buffer, instead of allocating one owned word \
+        //   per yielded word (this trades ergonomics for a lot less allocations)
+        let mut arena = TokenLexerArena {
+            buffer: String::with_capacity(self.text.len()),
+            tokens: Vec::new(),
+        };
+
+        while let Some(word) = self.next_word() {
+            if let (normalized, Some(term_hash), TokenDecisionOutcome::Yielded) = self.decide(word)
+            {
+                let start = arena.buffer.len();
+
+                arena.buffer.push_str(&normalized);
+                arena.tokens.push((start..arena.buffer.len(), term_hash));
+            }
+        }
+
+        arena
+    }
+
+    pub fn interned(self, interner: &mut TokenLexerInterner) -> TokenLexerInterned<'a, '_> {
+        // Yield words along with their dense identifier in a caller-owned interner, which is \
+        //   shared across texts (eg. to build dense postings, with no separate hashing step)
+        // Notice: the interner is mutably borrowed for the whole lifetime of the iterator, thus \
+        //   the iterator must be dropped before the interner can be passed to the next lexer.
+        TokenLexerInterned {
+            lexer: self,
+            interner,
+        }
+    }
+
+    pub fn offsets(self) -> TokenLexerOffsets<'a> {
+        // Yield words along with their byte range in the original text
+        TokenLexerOffsets { lexer: self }
+    }
+
+    pub fn with_expansion<F: FnMut(&str) -> Vec<String> + 'a>(mut self, expand: F) -> Self {
+        // Expand each yielded word with words provided by the caller (eg. synonyms looked up \
+        //   in a thesaurus, or domain codes), which get yielded after their word
+        // Notice: expanded words are yielded as-is (ie. they are not normalized), though they \
+        //   get de-duplicated along with all other words.
+        self.expansion = Some(Box::new(expand));
+
+        self
+    }
+
+    pub fn detection(&self) -> TokenLexerDetection {
+        self.detection
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.blank
+    }
+
+    pub fn counts(&self) -> &TokenLexerCounts {
+        // Notice: counts only cover the words that were consumed so far, thus the lexer should \
+        //   be iterated by reference until exhausted to get the counts for the whole text.
+        &self.counts
+    }
+
+    pub fn diff(
+        self,
+        previous: &HashSet<StoreTermHashed>,
+    ) -> (HashSet<StoreTermHashed>, HashSet<StoreTermHashed>) {
+        // Diff the terms of the text against a previous set of terms, returning the added and \
+        //   removed terms (eg. to update the index of an updated document, without re-pushing \
+        //   all of its terms)
+        // Notice: this should not be used on a lexer that shares its yields with other lexers, \
+        //   as terms that were already yielded by the other lexers would be seen as removed.
+        // Notice: terms are collected from the full iterator, thus companions (eg. shingles) \
+        //   are diffed as well, and the maximum number of yielded words applies.
+        let current = self
+            .map(|(_, term_hash)| term_hash)
+            .collect::<HashSet<StoreTermHashed>>();
+
+        let added = current.difference(previous).copied().collect();
+        let removed = previous.difference(&current).copied().collect();
+
+        (added, removed)
+    }
+
+    pub fn estimate_footprint(self) -> TokenLexerFootprint {
+        // Estimate how much indexing the text would cost, from its unique terms and their \
+        //   hashes (eg. to let operators spot documents that are expensive to index)
+        // Notice: this is an approximation, which does not account for how the store lays out \
+        //   terms, nor for terms that are already present in the index.
+        let mut terms = HashSet::new();
+        let mut footprint = TokenLexerFootprint::default();
+
+        for (word, term_hash) in self {
+            if terms.insert(term_hash) {
+                footprint.terms += 1;
+                footprint.term_bytes += word.len();
+            }
+        }
+
+        footprint.hash_bytes = footprint.terms * (StoreTermHashed::BITS / 8) as usize;
+
+        footprint
+    }
+
+    fn next_word(&mut self) -> Option<&'a str> {
+        // Consume parts of the previous word first (if it was split)
+        if let Some(part) = self.splits.pop_front() {
+            return Some(part);
+        }
+
+        // Consume words that were looked ahead first (if any)
+        let word = self.lookahead.pop_front().or_else(|| self.words.next())?;
+
+        // Re-detect locale at the start of each window of words? (if enabled)
+        if let Some(window_words) = self.options.redetect_every_words {
+            if window_words > 0 && self.words_count.is_multiple_of(window_words) {
+                self.redetect_lang(word, window_words);
+            }
+
+            self.words_count += 1;
+        }
+
+        // Split word where letters and digits meet? (if enabled)
+        if self.options.split_digits != TokenLexerDigitsSplit::Disabled {
+            Self::split_digits(word, &mut self.splits);
+
+            if !self.splits.is_empty() && self.options.split_digits == TokenLexerDigitsSplit::Parts
+            {
+                return self.splits.pop_front();
+            }
+        }
+
+        Some(word)
+    }
+
+    fn split_digits(word: &'a str, splits: &mut VecDeque<&'a str>) {
+        let mut part_start = 0;
+        let mut part_is_digit = None;
+
+        for (index, character) in word.char_indices() {
+            // Characters that are neither letters nor digits (eg. combining marks) stick to \
+            //   the current part
+            let is_digit = if character.is_numeric() {
+                true
+            } else if character.is_alphabetic() {
+                false
+            } else {
+                continue;
+            };
+
+            match part_is_digit {
+                Some(part_is_digit) if part_is_digit != is_digit => {
+                    splits.push_back(&word[part_start..index]);
+
+                    part_start = index;
+                }
+                _ => {}
+            }
+
+            part_is_digit = Some(is_digit);
+        }
+
+        // Word has at least one split point? (commit its last part)
+        if part_start > 0 {
+            splits.push_back(&word[part_start..]);
+        }
+    }
+
+    fn redetect_lang(&mut self, word: &str, window_words: usize) {
+        // Fill lookahead with the upcoming words of the window
+        while self.lookahead.len() < window_words - 1 {
+            match self.words.next() {
+                Some(next_word) => self.lookahead.push_back(next_word),
+                None => break,
+            }
+        }
+
+        let window_text = self.lookahead.iter().take(window_words - 1).fold(
+            word.to_string(),
+            |mut window_text, next_word| {
+                window_text.push(' ');
+                window_text.push_str(next_word);
+
+                window_text
+            },
+        );
+
+        // Detect locale of the window (only if the locale was detected in the first place), \
+        //   as text may switch languages mid-document (eg. bilingual English-Spanish text)
+        // Notice: if no locale could be detected for the window, the previous locale is kept.
+        let safe_text =
+            TokenLexerBuilder::truncate_detect_text(&window_text, self.options.detect_max_chars);
+
+        let window_locale = match self.mode {
+            TokenLexerMode::NormalizeAndCleanup(None) => {
+                TokenLexerBuilder::detect_lang_override(safe_text, &self.options).or_else(|| {
+                    TokenLexerBuilder::detect_lang(safe_text, self.options.detect_present_scripts)
+                })
+            }
+            TokenLexerMode::NormalizeAndCleanupPriority(ref langs) => {
+                TokenLexerBuilder::detect_lang_priority(safe_text, langs)
+            }
+            _ => None,
+        };
+
+        if let Some(window_locale) = window_locale {
+            if self.locale != Some(window_locale) {
+                debug!(
+                    "lexer switched locale to: {} from window text: {}",
+                    window_locale, window_text
+                );
+
+                self.locale = Some(window_locale);
+                self.stopwords = Self::locale_stopwords(&self.mode, self.locale, &self.words);
+            }
+        }
+    }
+
+    fn is_preserved(&mut self, word: &str) -> bool {
+        // Word follows the first word of a matched phrase?
+        if self.preserved > 0 {
+            self.preserved -= 1;
+
+            return true;
+        }
+
+        // Look ahead for a phrase starting with this word, whose words will then be exempted \
+        //   from stopword removal (eg. 'The Who', which is otherwise made of stopwords only)
+        if let Some((index, phrase_len)) =
+            self.match_phrase(|options| &options.preserve_phrases, word)
+        {
+            debug!(
+                "lexer matched preserved phrase: {}",
+                self.options.preserve_phrases[index]
+            );
+
+            self.preserved = phrase_len;
+
+            return true;
+        }
+
+        false
+    }
+
+    fn is_stopword_phrase(&mut self, word: &str) -> bool {
+        // Word follows the first word of a matched stopword phrase?
+        if self.stopped > 0 {
+            self.stopped -= 1;
+
+            return true;
+        }
+
+        // Look ahead for a stopword phrase starting with this word, whose words will then be \
+        //   removed together (eg. 'such as'), even if they are not stopwords on their own
+        if let Some((index, phrase_len)) =
+            self.match_phrase(|options| &options.stopword_phrases, word)
+        {
+            debug!(
+                "lexer matched stopword phrase: {}",
+                self.options.stopword_phrases[index]
+            );
+
+            self.stopped = phrase_len;
+
+            return true;
+        }
+
+        false
+    }
+
+    fn match_phrase(
+        &mut self,
+        phrases: fn(&TokenLexerOptions) -> &Vec<String>,
+        word: &str,
+    ) -> Option<(usize, usize)> {
+        // Match phrases starting with this word, against the upcoming words (returning the \
+        //   matched phrase index, and the count of upcoming words that it spans)
+        for (index, phrase) in phrases(&self.options).iter().enumerate() {
+            let mut phrase_words = phrase.split_whitespace();
+
+            let is_first = phrase_words.next().is_some_and(|phrase_word| {
+                self.options
+                    .normalize_word_borrowed(phrase_word, self.locale)
+                    == word
+            });
+
+            if !is_first {
+                continue;
+            }
+
+            let mut phrase_len = 0;
+            let mut is_match = true;
+
+            for phrase_word in phrase_words {
+                // Fill lookahead with enough words to compare against the phrase
+                if phrase_len == self.lookahead.len() {
+                    match self.words.next() {
+                        Some(next_word) => self.lookahead.push_back(next_word),
+                        None => {
+                            is_match = false;
+
+                            break;
+                        }
+                    }
+                }
+
+                if self
+                    .options
+                    .normalize_word_borrowed(self.lookahead[phrase_len], self.locale)
+                    != self
+                        .options
+                        .normalize_word_borrowed(phrase_word, self.locale)
+                {
+                    is_match = false;
+
+                    break;
+                }
+
+                phrase_len += 1;
+            }
+
+            if is_match {
+                return Some((index, phrase_len));
+            }
+        }
+
+        None
+    }
+
+    fn shingle(&mut self, word: &str, outcome: &TokenDecisionOutcome) {
+        let shingle_sizes = match self.options.shingle_sizes {
+            Some(ref shingle_sizes) => shingle_sizes.clone(),
+            None => return,
+        };
+
+        match outcome {
+            TokenDecisionOutcome::Yielded | TokenDecisionOutcome::DroppedDuplicate => {
+                // Notice: duplicate words still take part in shingles, as a phrase may repeat \
+                //   words that were already yielded (eg. 'bye bye').
+                if self.shingle_words.len() == *shingle_sizes.end() {
+                    self.shingle_words.pop_front();
+                }
+
+                self.shingle_words.push_back(word.to_string());
+            }
+            TokenDecisionOutcome::DroppedStopword if self.options.shingle_skip_stopwords => {
+                // Stopwords are skipped over, as they are not indexed (thus queried phrases \
+                //   would not contain them either)
+                return;
+            }
+            _ => {
+                // Break the current phrase
+                self.shingle_words.clear();
+
+                return;
+            }
+        }
+
+        // Generate all shingles that end with this word (single words are not shingles)
+        for shingle_size in shingle_sizes {
+            if shingle_size < 2 || shingle_size > self.shingle_words.len() {
+                continue;
+            }
+
+            let shingle = self
+                .shingle_words
+                .iter()
+                .skip(self.shingle_words.len() - shingle_size)
+                .map(String::as_str)
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            let term_hash = StoreTermHash::from(&shingle);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded shingle: {}", shingle);
+
+                self.companions.push_back((shingle, term_hash));
+            }
+        }
+    }
+
+    fn is_proper_noun(&mut self, word: &str) -> bool {
+        // Notice: words that are not slices of the text (eg. pre-tokenized words) have no \
+        //   position, thus cannot be told apart from sentence-initial words.
+        let text_range =
+            self.text.as_ptr() as usize..=self.text.as_ptr() as usize + self.text.len();
+
+        if !text_range.contains(&(word.as_ptr() as usize)) {
+            return false;
+        }
+
+        let start = word.as_ptr() as usize - self.text.as_ptr() as usize;
+
+        // Word starts a sentence? (ie. it is the first word, or sentence-ending punctuation \
+        //   stands between the previous word and this word)
+        let is_sentence_initial = match self.last_word_end {
+            Some(last_word_end) if last_word_end <= start => {
+                self.text[last_word_end..start].contains(PUNCTUATION_SENTENCE_ENDS)
+            }
+            Some(_) => false,
+            None => true,
+        };
+
+        self.last_word_end = Some(start + word.len());
+
+        !is_sentence_initial && word.chars().next().is_some_and(char::is_uppercase)
+    }
+
+    fn mark_detection(&mut self, detection: TokenLexerDetection) {
+        self.detection = detection;
+
+        // Tag text with the undetermined marker? (if enabled, as to be able to search for, or \
+        //   filter out texts whose locale could not be detected)
+        if detection == TokenLexerDetection::Undetermined && self.options.mark_undetermined {
+            let term_hash = StoreTermHash::from(TOKEN_UNDETERMINED_MARKER);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded undetermined marker");
+
+                self.companions
+                    .push_back((TOKEN_UNDETERMINED_MARKER.to_string(), term_hash));
+            }
+        }
+    }
+
+    fn mark_proper_noun(&mut self, word: &str, outcome: &TokenDecisionOutcome) {
+        // Only mark words that are indexed (stopwords and junk words are not)
+        if let TokenDecisionOutcome::Yielded | TokenDecisionOutcome::DroppedDuplicate = outcome {
+            let marker = format!("{}{}", word, TOKEN_PROPER_NOUN_SUFFIX);
+            let term_hash = StoreTermHash::from(&marker);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded proper noun marker: {}", marker);
+
+                self.companions.push_back((marker, term_hash));
+            }
+        }
+    }
+
+    fn keep_original_case(&mut self, original: &str, word: &str, outcome: &TokenDecisionOutcome) {
+        // Yield the word as it was written in the text, if it differs in case from the word \
+        //   (eg. 'NASA' yields both 'nasa' and 'NASA'), which lets case-sensitive queries match \
+        //   (eg. on acronyms), while case-insensitive queries still match the word
+        // Notice: the original word is yielded as-is, ie. it is not normalized.
+        if let TokenDecisionOutcome::Yielded | TokenDecisionOutcome::DroppedDuplicate = outcome {
+            if original == word || !original.chars().any(char::is_uppercase) {
+                return;
+            }
+
+            let term_hash = StoreTermHash::from(original);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded original-case word: {}", original);
+
+                self.companions.push_back((original.to_string(), term_hash));
+            }
+        }
+    }
+
+    fn is_punctuation_only(word: &str) -> bool {
+        // Notice: ideographic characters are alphanumeric, while emoji are not (though they \
+        //   are kept, as they are meaningful on their own).
+        !word.chars().any(|character| {
+            character.is_alphanumeric()
+                || RANGES_PICTOGRAPHIC
+                    .iter()
+                    .any(|(start, end)| character >= *start && character <= *end)
+        })
+    }
+
+    fn is_syllable_delimited(text: &str) -> bool {
+        // Most letters of the text are written in a tsheg-delimited script? (as UAX29 does not \
+        //   segment those scripts into syllables)
+        // Notice: mixed-script texts where such letters are not dominant still get segmented \
+        //   with UAX29, as syllable delimiters would not segment words of the other scripts.
+        if text.is_ascii() {
+            return false;
+        }
+
+        let (mut delimited_count, mut letters_count) = (0, 0);
+
+        for character in text.chars().filter(|character| character.is_alphabetic()) {
+            if RANGES_SYLLABLE_DELIMITED
+                .iter()
+                .any(|(start, end)| character >= *start && character <= *end)
+            {
+                delimited_count += 1;
+            }
+
+            letters_count += 1;
+        }
+
+        if delimited_count > 0 && delimited_count * 2 <= letters_count {
+            debug!(
+                "text is not mostly syllable-delimited ({}/{} letters), falling back to uax29",
+                delimited_count, letters_count
+            );
+
+            return false;
+        }
+
+        delimited_count > 0
+    }
+
+    #[cfg(feature = "tokenizer-khmer")]
+    fn is_khmer(text: &str) -> bool {
+        // Most letters of the text are Khmer? (as UAX29 does not segment Khmer words, which \
+        //   are not separated with spaces)
+        if text.is_ascii() {
+            return false;
+        }
+
+        let (mut khmer_count, mut letters_count) = (0, 0);
+
+        for character in text.chars().filter(|character| character.is_alphabetic()) {
+            if Self::is_khmer_character(character) {
+                khmer_count += 1;
+            }
+
+            letters_count += 1;
+        }
+
+        khmer_count > 0 && khmer_count * 2 > letters_count
+    }
+
+    #[cfg(feature = "tokenizer-khmer")]
+    fn is_khmer_character(character: char) -> bool {
+        RANGES_KHMER
+            .iter()
+            .any(|(start, end)| character >= *start && character <= *end)
+    }
+
+    fn is_syllable_separator(character: char) -> bool {
+        RANGES_SYLLABLE_DELIMITERS
+            .iter()
+            .any(|(start, end)| character >= *start && character <= *end)
+            || character.is_whitespace()
+            || character.is_ascii_punctuation()
+    }
+
+    fn is_run_separator(character: char) -> bool {
+        // Notice: combining marks and modifier letters are not separators, as they are part \
+        //   of the word they are attached to (eg. a decomposed 'é').
+        !character.is_alphanumeric()
+            && !RANGES_MODIFIERS
+                .iter()
+                .any(|(start, end)| character >= *start && character <= *end)
+    }
+
+    fn expand(&mut self, word: &str, outcome: &TokenDecisionOutcome) {
+        if *outcome != TokenDecisionOutcome::Yielded {
+            return;
+        }
+
+        let expanded = match self.expansion.as_mut() {
+            Some(expand) => expand(word),
+            None => return,
+        };
+
+        for expanded_word in expanded {
+            if expanded_word.is_empty() {
+                continue;
+            }
+
+            let term_hash = StoreTermHash::from(&expanded_word);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded expanded word: {}", expanded_word);
+
+                self.companions.push_back((expanded_word, term_hash));
+            }
+        }
+    }
+
+    fn stem(&mut self, word: &str, outcome: &TokenDecisionOutcome) {
+        // Yield the stem of the word after the word itself (if it differs from the word)
+        // Notice: the stemmer is passed the word in its configured case, which is upper-case \
+        //   if words are upper-cased.
+        if *outcome != TokenDecisionOutcome::Yielded {
+            return;
+        }
+
+        let stemmed = self.options.stem_word(Cow::Borrowed(word), self.locale);
+
+        if let Cow::Owned(stemmed) = stemmed {
+            let term_hash = StoreTermHash::from(&stemmed);
+
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded stemmed word: {}", stemmed);
+
+                self.companions.push_back((stemmed, term_hash));
+            }
+        }
+    }
+
+    fn reverse(&mut self, word: &str, outcome: &TokenDecisionOutcome, max_chars: usize) {
+        // Yield the reversed word, which lets suffix queries match words (eg. '*ing'); the \
+        //   query side must reverse the suffix (ie. 'gni'), and match it as a prefix against \
+        //   the reversed words. Reversed words are only yielded for words up to a maximum \
+        //   length, as to bound the cost of indexing them.
+        // Notice: words get reversed per-grapheme, as to keep combining marks attached to \
+        //   their base character.
+        if *outcome != TokenDecisionOutcome::Yielded || word.chars().count() > max_chars {
+            return;
+        }
+
+        let reversed = word.graphemes(true).rev().collect::<String>();
+        let term_hash = StoreTermHash::from(&reversed);
+
+        if self.yields.insert(term_hash) {
+            debug!("lexer yielded reversed word: {}", reversed);
+
+            self.companions.push_back((reversed, term_hash));
+        }
+    }
+
+    fn locale_stopwords(
+        mode: &TokenLexerMode,
+        locale: Option<Language>,
+        words: &TokenLexerWords,
+    ) -> Option<LexerStopWords> {
+        // Hold the stopwords of the locale for the whole text, as to avoid looking them up \
+        //   again for each word (they are not needed if stopwords are not removed)
+        // Notice: Khmer texts have no locale, thus their stopwords are looked up from the \
+        //   script that they got segmented for.
+        match (mode, locale, words) {
+            (TokenLexerMode::NormalizeOnly, _, _) => None,
+            (_, Some(locale), _) => Some(LexerStopWord::lang(locale)),
+            #[cfg(feature = "tokenizer-khmer")]
+            (_, None, TokenLexerWords::Khmer(_)) => LexerStopWord::script(Script::Khmer),
+            (_, None, _) => None,
+        }
+    }
+
+    fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords
+            .as_ref()
+            .is_some_and(|stopwords| stopwords.contains(word))
+    }
+
+    fn is_modifiers_only(word: &str) -> bool {
+        word.chars().all(|character| {
+            RANGES_MODIFIERS
+                .iter()
+                .any(|(start, end)| character >= *start && character <= *end)
+        })
+    }
+
+    fn decide_streamed(
+        &mut self,
+        word: &str,
+    ) -> Option<(Option<StoreTermHashed>, TokenDecisionOutcome)> {
+        // Streaming is only possible if the word is only lower-cased by normalization (as \
+        //   options that do more than this disable streaming, see 'is_streamable()'), and if \
+        //   the word would not be rejected. Words that are already lower-cased do not need to \
+        //   be streamed either, as they do not get copied in the first place.
+        // Notice: words that hold any non-alphanumeric character never get streamed, as those \
+        //   characters are either punctuation, or get stripped or mapped by normalization (eg. \
+        //   control characters, joiners, invisible hyphens or typographic punctuation).
+        if word.len() > self.options.max_word_bytes
+            || TokenLexerCaseMapping::is_lowercased(word)
+            || !word.chars().all(char::is_alphanumeric)
+            || (self.options.drop_modifiers_only && Self::is_modifiers_only(word))
+            || (self.options.case_mapping == TokenLexerCaseMapping::Full && word.contains('Σ'))
+        {
+            return None;
+        }
+
+        let term_hash = match self.options.case_mapping {
+            TokenLexerCaseMapping::Full => {
+                StoreTermHash::from_chars(word.chars().flat_map(char::to_lowercase))
+            }
+            TokenLexerCaseMapping::Simple => {
+                StoreTermHash::from_chars(word.chars().map(TokenLexerCaseMapping::lowercase_simple))
+            }
+        };
+
+        let outcome = if self.yields.insert(term_hash) {
+            debug!("lexer yielded streamed word: {}", word);
+
+            LexerMetrics::count(LexerMetricsCounter::TokensYielded);
+
+            TokenDecisionOutcome::Yielded
+        } else {
+            TokenDecisionOutcome::DroppedDuplicate
+        };
+
+        self.counts.count(&outcome);
+
+        Some((Some(term_hash), outcome))
+    }
+
+    fn decide<'w>(
+        &mut self,
+        word: &'w str,
+    ) -> (Cow<'w, str>, Option<StoreTermHashed>, TokenDecisionOutcome) {
+        let (normalized, term_hash, outcome) = self.decide_raw(word);
+
+        (normalized.unwrap_or(Cow::Borrowed("")), term_hash, outcome)
+    }
+
+    fn decide_raw<'w>(
+        &mut self,
+        word: &'w str,
+    ) -> (
+        Option<Cow<'w, str>>,
+        Option<StoreTermHashed>,
+        TokenDecisionOutcome,
+    ) {
+        let decision = self.decide_word(word);
+
+        self.counts.count(&decision.2);
+
+        decision
+    }
+
+    fn decide_word<'w>(
+        &mut self,
+        word: &'w str,
+    ) -> (
+        Option<Cow<'w, str>>,
+        Option<StoreTermHashed>,
+        TokenDecisionOutcome,
+    ) {
+        // Reject over-sized words before they get normalized (a malformed input with no \
+        //   segmentation point may produce a single enormous word, that we do not want to \
+        //   allocate a lower-cased copy of)
+        if word.len() > self.options.max_word_bytes {
+            warn!(
+                "lexer did not yield word because: word is too long ({}/{} bytes)",
+                word.len(),
+                self.options.max_word_bytes
+            );
+
+            return (None, None, TokenDecisionOutcome::DroppedJunk);
+        }
+
+        // Reject words that are only made of combining marks or modifier letters? (if enabled)
+        if self.options.drop_modifiers_only && Self::is_modifiers_only(word) {
+            debug!(
+                "lexer did not yield word: {} because: word has no base character",
+                word
+            );
+
+            return (None, None, TokenDecisionOutcome::DroppedJunk);
+        }
+
+        // Reject words that are only made of punctuation? (if enabled, eg. a stray '—' that \
+        //   some tokenizers yield as a word)
+        if self.options.drop_punctuation_only && Self::is_punctuation_only(word) {
+            debug!(
+                "lexer did not yield word: {} because: word has no alphanumeric character",
+                word
+            );
+
+            return (None, None, TokenDecisionOutcome::DroppedJunk);
+        }
+
+        // Normalize word (the same way a query term would be normalized)
+        let word = self.options.normalize_word_borrowed(word, self.locale);
+
+        // Check if normalized word is part of a preserved phrase? (if any phrase is registered)
+        let is_preserved = !self.options.preserve_phrases.is_empty() && self.is_preserved(&word);
+
+        // Check if normalized word is part of a stopword phrase? (if any phrase is registered)
+        // Notice: single words are still checked against stopwords on their own, thus only \
+        //   texts with registered phrases pay for the lookahead.
+        let is_stopword_phrase = self.mode != TokenLexerMode::NormalizeOnly
+            && !self.options.stopword_phrases.is_empty()
+            && self.is_stopword_phrase(&word);
+
+        // Reject words that are too short for the locale? (if enabled)
+        if !is_preserved && self.options.min_word_chars != TokenLexerMinWordChars::Disabled {
+            let min_chars = self.options.min_word_chars.for_locale(self.locale);
+
+            if word.chars().count() < min_chars {
+                debug!(
+                    "lexer did not yield word: {} because: word is too short (under {} chars)",
+                    word, min_chars
+                );
+
+                return (Some(word), None, TokenDecisionOutcome::DroppedJunk);
+            }
+        }
+
+        // Check if normalized word is a stop-word? (if should normalize and cleanup)
+        if self.mode == TokenLexerMode::NormalizeOnly
+            || is_preserved
+            || self.keep_stopwords
+            || (!is_stopword_phrase && !self.is_stopword(&word))
+        {
+            // Stem word? (if a stemmer is set, and unstemmed words are not to be kept)
+            // Notice: words that are part of a preserved phrase are never stemmed.
+            let word = if !is_preserved && self.options.stemming == TokenLexerStemming::Stemmed {
+                self.options.stem_word(word, self.locale)
+            } else {
+                word
+            };
+
+            // Map word to the configured case (words are compared in lower-case up to there, \
+            //   as stopwords are lower-case)
+            let word = self.options.normalize_case(word);
+
+            // Reject words that are not allowed? (if an allow filter is set, eg. to only index \
+            //   structured words such as product codes)
+            // Notice: the filter gets called with the normalized word, and it is not called on \
+            //   shingles nor on other companions.
+            if let Some(ref allow_filter) = self.options.allow_filter {
+                if !allow_filter(&word) {
+                    debug!(
+                        "lexer did not yield word: {} because: word is not allowed",
+                        word
+                    );
+
+                    return (Some(word), None, TokenDecisionOutcome::DroppedJunk);
+                }
+            }
+
+            // Hash the term (this is used by all iterator consumers, as well as internally \
+            //   in the iterator to keep track of already-yielded words in a space-optimized \
+            //   manner, ie. by using 32-bit unsigned integer hashes)
+            let term_hash = StoreTermHash::from(&word);
+
+            // Check if word was not already yielded? (we return unique words)
+            if self.yields.insert(term_hash) {
+                debug!("lexer yielded word: {}", word);
+
+                LexerMetrics::count(LexerMetricsCounter::TokensYielded);
+
+                (Some(word), Some(term_hash), TokenDecisionOutcome::Yielded)
+            } else {
+                debug!(
+                    "lexer did not yield word: {} because: word already yielded",
+                    word
+                );
+
+                (
+                    Some(word),
+                    Some(term_hash),
+                    TokenDecisionOutcome::DroppedDuplicate,
+                )
+            }
+        } else {
+            debug!(
+                "lexer did not yield word: {} because: word is a stop-word",
+                word
+            );
+
+            LexerMetrics::count(LexerMetricsCounter::StopwordsDropped);
+
+            (Some(word), None, TokenDecisionOutcome::DroppedStopword)
+        }
+    }
+}
+
+impl TokenLexerMinWordChars {
+    fn for_locale(&self, locale: Option<Language>) -> usize {
+        // Use the overridden minimum for the locale? (if any)
+        if let TokenLexerMinWordChars::LanguageOverrides(overrides) = self {
+            if let Some((_, min_chars)) = overrides
+                .iter()
+                .find(|(language, _)| Some(*language) == locale)
+            {
+                return *min_chars;
+            }
+        }
+
+        // Notice: words are not filtered if the locale is unknown, as single characters may \
+        //   be meaningful words in the text language.
+        match locale {
+            Some(Language::Chinese) | Some(Language::Japanese) | Some(Language::Korean) | None => {
+                TEXT_WORD_MIN_CHARS_IDEOGRAPHIC
+            }
+            Some(_) => TEXT_WORD_MIN_CHARS_DEFAULT,
+        }
+    }
+}
+
+#[cfg(feature = "tokenizer-chinese")]
+impl TokenLexerChineseVariant {
+    fn convert<'w>(&self, word: Cow<'w, str>) -> Cow<'w, str> {
+        let target = match self {
+            TokenLexerChineseVariant::Unchanged => return word,
+            TokenLexerChineseVariant::Simplified => zhconv::Variant::ZhHans,
+            TokenLexerChineseVariant::Traditional => zhconv::Variant::ZhHant,
+        };
+
+        let converted = zhconv::zhconv(&word, target);
+
+        if converted != word {
+            Cow::Owned(converted)
+        } else {
+            word
+        }
+    }
+}
+
+impl TokenLexerControls {
+    fn is_stripped(&self, character: char) -> bool {
+        match self {
+            TokenLexerControls::Tokenizer => false,
+            TokenLexerControls::Strip => Self::is_control(character),
+            TokenLexerControls::StripWithPrivateUse => {
+                Self::is_control(character)
+                    || RANGES_PRIVATE_USE
+                        .iter()
+                        .any(|(start, end)| character >= *start && character <= *end)
+            }
+        }
+    }
+
+    fn is_control(character: char) -> bool {
+        // Notice: this covers both C0 and C1 control characters, apart from whitespace ones.
+        character.is_control() && !character.is_whitespace()
+    }
+}
+
+impl TokenLexerHanOnly {
+    fn resolve(&self, locale: Option<Language>, text: &str) -> Option<Language> {
+        let preferred = match self {
+            TokenLexerHanOnly::Detected => return locale,
+            TokenLexerHanOnly::Chinese => Language::Chinese,
+            TokenLexerHanOnly::Japanese => Language::Japanese,
+        };
+
+        // Notice: only texts detected as either Chinese or Japanese are resolved, as other \
+        //   languages cannot be written with Han characters only.
+        match locale {
+            Some(Language::Chinese) | Some(Language::Japanese) if Self::is_han_only(text) => {
+                debug!("resolving han-only text to preferred locale: {}", preferred);
+
+                Some(preferred)
+            }
+            _ => locale,
+        }
+    }
+
+    fn is_han_only(text: &str) -> bool {
+        let mut buffer = [0; 4];
+        let mut letters = text
+            .chars()
+            .filter(|character| character.is_alphabetic())
+            .peekable();
+
+        letters.peek().is_some()
+            && letters.all(|character| {
+                detect_script(character.encode_utf8(&mut buffer)) == Some(Script::Mandarin)
+            })
+    }
+}
+
+impl TokenLexerWidthFolding {
+    fn for_locale(&self, locale: Option<Language>) -> bool {
+        match self {
+            TokenLexerWidthFolding::Disabled => false,
+            TokenLexerWidthFolding::LanguageDefaults => matches!(
+                locale,
+                Some(Language::Chinese) | Some(Language::Japanese) | Some(Language::Korean)
+            ),
+            TokenLexerWidthFolding::Enabled => true,
+        }
+    }
+}
+
+impl TokenLexerIdentifierPattern {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            TokenLexerIdentifierPattern::Uuid => {
+                text.len() == 36
+                    && text.bytes().enumerate().all(|(index, byte)| match index {
+                        8 | 13 | 18 | 23 => byte == b'-',
+                        _ => byte.is_ascii_hexdigit(),
+                    })
+            }
+            TokenLexerIdentifierPattern::Hex(min_chars) => {
+                text.len() >= *min_chars && text.bytes().all(|byte| byte.is_ascii_hexdigit())
+            }
+            TokenLexerIdentifierPattern::Base64(min_chars) => {
+                let data = text.trim_end_matches('=');
+
+                // Notice: Base64 data must mix digits with lower-case and upper-case letters, \
+                //   as long words would otherwise be mistaken for identifiers.
+                text.len() >= *min_chars
+                    && text.len() - data.len() <= 2
+                    && data.bytes().all(|byte| {
+                        byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'-' | b'_')
+                    })
+                    && data.bytes().any(|byte| byte.is_ascii_digit())
+                    && data.bytes().any(|byte| byte.is_ascii_lowercase())
+                    && data.bytes().any(|byte| byte.is_ascii_uppercase())
+            }
+        }
+    }
+}
+
+impl TokenLexerCounts {
+    fn count(&mut self, outcome: &TokenDecisionOutcome) {
+        self.seen += 1;
+
+        match outcome {
+            TokenDecisionOutcome::Yielded => self.unique += 1,
+            TokenDecisionOutcome::DroppedStopword => self.stopwords += 1,
+            TokenDecisionOutcome::DroppedDuplicate => self.duplicates += 1,
+            TokenDecisionOutcome::DroppedJunk => self.junk += 1,
+        }
+    }
+}
+
+impl TokenLexerOptions {
+    fn is_streamable(&self) -> bool {
+        // Options only lower-case words, do not reject any word that is otherwise valid, and \
+        //   do not yield companions? (this is what lets words be hashed while they get \
+        //   lower-cased; the 'NormalizeOnly' mode has no locale, thus no locale-specific rule \
+        //   applies)
+        self.case == TokenLexerCase::Lower
+            && self.allow_filter.is_none()
+            && self.stemmer.is_none()
+            && self.collapse_repeats.is_none()
+            && self.min_word_chars == TokenLexerMinWordChars::Disabled
+            && self.preserve_phrases.is_empty()
+            && !self.fold_width.for_locale(None)
+            && !self.fold_ligatures
+            && !self.mark_proper_nouns
+            && !self.keep_original_case
+            && self.reverse_words.is_none()
+            && self.shingle_sizes.is_none()
+    }
+
+    fn merged_characters(&self) -> Vec<char> {
+        let mut merged = self.retain_punctuation.clone();
+
+        // Merge words across connector punctuation? (if enabled, overriding the tokenizer)
+        match self.join_connectors {
+            TokenLexerConnectors::Tokenizer => {}
+            TokenLexerConnectors::Underscore => merged.push('_'),
+            TokenLexerConnectors::Punctuation => merged.extend_from_slice(CONNECTORS_PUNCTUATION),
+        }
+
+        merged
+    }
+
+    fn merged_prefixes(&self) -> Vec<char> {
+        // Merge hashtag and mention symbols with the word that they lead? (if enabled)
+        if self.social_tags {
+            SOCIAL_TAGS_PREFIXES.to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn normalize_word(&self, word: &str, locale: Option<Language>) -> String {
+        self.normalize_case(self.normalize_word_borrowed(word, locale))
+            .into_owned()
+    }
+
+    fn stem_word<'w>(&self, word: Cow<'w, str>, locale: Option<Language>) -> Cow<'w, str> {
+        // Notice: the word is left as-is if it has no stem (or if its stem is empty), as the \
+        //   stemmer may not support the locale.
+        match self
+            .stemmer
+            .as_ref()
+            .and_then(|stemmer| stemmer(&word, locale))
+        {
+            Some(stemmed) if !stemmed.is_empty() && stemmed != word => Cow::Owned(stemmed),
+            _ => word,
+        }
+    }
+
+    fn normalize_case<'w>(&self, word: Cow<'w, str>) -> Cow<'w, str> {
+        match self.case {
+            TokenLexerCase::Lower => word,
+            TokenLexerCase::Upper => Cow::Owned(self.case_mapping.uppercase(&word)),
+        }
+    }
+
+    fn normalize_word_borrowed<'w>(&self, word: &'w str, locale: Option<Language>) -> Cow<'w, str> {
+        // Lower-case word
+        // Notice: unfortunately, as Rust is unicode-aware, we need to convert the str slice \
+        //   to a heap-indexed String; as lower-cased characters may change in bit size.
+        let word = self.case_mapping.lowercase_locale(word, locale);
+
+        // Fold full-width and half-width characters? (if enabled for locale, eg. 'ａｂｃ' \
+        //   becomes 'abc', and 'ｶﾀｶﾅ' becomes 'カタカナ')
+        // Notice: this only covers the halfwidth and fullwidth forms block, unlike a full NFKC \
+        //   normalization, which would also fold other compatibility characters.
+        let word = if self.fold_width.for_locale(locale)
+            && word
+                .chars()
+                .any(|character| Self::width_folded(character).is_some())
+        {
+            Cow::Owned(Self::fold_width(&word))
+        } else {
+            word
+        };
+
+        // Remove invisible hyphenation marks (eg. 'hyphen\u{00AD}ation' becomes 'hyphenation')
+        let word = if word.contains(HYPHENS_INVISIBLE) {
+            Cow::Owned(word.replace(HYPHENS_INVISIBLE, ""))
+        } else {
+            word
+        };
+
+        // Remove control characters? (if enabled, eg. 'invo\u{0007}ice' becomes 'invoice')
+        let word = if word
+            .chars()
+            .any(|character| self.strip_controls.is_stripped(character))
+        {
+            Cow::Owned(
+                word.chars()
+                    .filter(|character| !self.strip_controls.is_stripped(*character))
+                    .collect(),
+            )
+        } else {
+            word
+        };
+
+        // Remove zero-width joiners? (if enabled, eg. 'co\u{200D}operate' becomes 'cooperate')
+        // Notice: joiners are removed after invisible hyphens (which are always removed), and \
+        //   are preserved in words that hold an emoji or an Indic character, as they either \
+        //   join emoji into a single emoji or change how Indic characters are rendered.
+        let word = if self.strip_joiners && Self::is_joiner_strippable(&word) {
+            Cow::Owned(word.replace(JOINER_ZERO_WIDTH, ""))
+        } else {
+            word
+        };
+
+        // Remove emoji variation selectors and skin tone modifiers? (if enabled, as to map all \
+        //   variants of an emoji to its base emoji, eg. '👍🏽' and '👍️' become '👍')
+        // Notice: this happens after zero-width joiners are removed, which are preserved in \
+        //   emoji, thus compound emoji keep their joiners (eg. '👩🏽\u{200D}💻' becomes \
+        //   '👩\u{200D}💻').
+        let word = if self.strip_emoji_modifiers && word.contains(MODIFIERS_EMOJI) {
+            Cow::Owned(word.replace(MODIFIERS_EMOJI, ""))
+        } else {
+            word
+        };
+
+        // Map typographic punctuation to its ASCII equivalent? (if enabled)
+        // Notice: word boundaries are the same for both typographic and ASCII punctuation \
+        //   (eg. an en dash breaks words like an hyphen does), thus only punctuation found \
+        //   within a word needs to be mapped (eg. 'don’t').
+        let word = if self.normalize_typography
+            && word
+                .chars()
+                .any(|character| Self::typography_ascii(character).is_some())
+        {
+            Cow::Owned(Self::normalize_typography(&word))
+        } else {
+            word
+        };
+
+        // Fold ligatures into their letters? (if enabled, eg. 'ﬁle' becomes 'file')
+        // Notice: this only covers Latin typographic ligatures and Serbian/Croatian digraphs, \
+        //   unlike a full NFKC normalization, which would also fold other compatibility \
+        //   characters (eg. '²' into '2', or '½' into '1⁄2') that are often meaningful.
+        let word = if self.fold_ligatures
+            && word
+                .chars()
+                .any(|character| Self::ligature_letters(character).is_some())
+        {
+            Cow::Owned(Self::fold_ligatures(&word))
+        } else {
+            word
+        };
+
+        // Collapse runs of repeated letters? (if enabled, eg. 'looool' becomes 'lool')
+        let word = match self.collapse_repeats {
+            Some(collapse_length) if Self::has_repeats(&word) => {
+                Cow::Owned(Self::collapse_repeats(&word, collapse_length))
+            }
+            _ => word,
+        };
+
+        // Normalize numbers with separators? (if enabled, eg. '1,000,000' in English and \
+        //   '1.000.000' in German both become '1000000', and '3,14' in German becomes '3.14')
+        // Notice: UAX29 keeps numbers with separators as a single word, thus this makes all \
+        //   notations of a number map to the same word.
+        // Notice: numbers that do not follow the separator rules of the locale are left \
+        //   unchanged (eg. version numbers such as '1.2.3').
+        let number = if self.normalize_numbers && Self::is_separated_number(&word) {
+            Self::normalize_number(&word, locale)
+        } else {
+            None
+        };
+
+        let word = number.map_or(word, Cow::Owned);
+
+        // Strip clitics from word? (if enabled, as to only keep the content word)
+        let word = match locale {
+            Some(Language::Italian) if self.split_clitics => {
+                Self::strip_clitics(word, CLITICS_ELIDED_ITALIAN, &[])
+            }
+            Some(Language::Catalan) if self.split_clitics => {
+                Self::strip_clitics(word, CLITICS_ELIDED_CATALAN, CLITICS_ATTACHED_CATALAN)
+            }
+            _ => word,
+        };
+
+        match locale {
+            Some(Language::Ukrainian) | Some(Language::Belarusian)
+                if word.contains(APOSTROPHES_CYRILLIC) =>
+            {
+                // Unify apostrophe variants, as all of those are typed interchangeably
+                Cow::Owned(word.replace(APOSTROPHES_CYRILLIC, "'"))
+            }
+            Some(Language::Catalan) if word.contains(MIDDLE_DOTS_CATALAN) => {
+                Cow::Owned(self.normalize_catalan_middle_dot(&word))
+            }
+            #[cfg(feature = "tokenizer-chinese")]
+            Some(Language::Chinese) => self.chinese_variant.convert(word),
+            _ => word,
+        }
+    }
+
+    fn is_joiner_strippable(word: &str) -> bool {
+        word.contains(JOINER_ZERO_WIDTH)
+            && !word.chars().any(|character| {
+                RANGES_INDIC
+                    .iter()
+                    .chain(RANGES_PICTOGRAPHIC.iter())
+                    .any(|(start, end)| character >= *start && character <= *end)
+            })
+    }
+
+    fn is_separated_number(word: &str) -> bool {
+        let (mut has_separator, mut previous_separator) = (false, true);
+
+        for character in word.chars() {
+            if NUMBER_SEPARATORS.contains(&character) {
+                // Notice: separators must sit between digits (eg. '1,,000' is not a number)
+                if previous_separator {
+                    return false;
+                }
+
+                has_separator = true;
+                previous_separator = true;
+            } else if character.is_ascii_digit() {
+                previous_separator = false;
+            } else {
+                return false;
+            }
+        }
+
+        has_separator && !previous_separator
+    }
+
+    fn normalize_number(word: &str, locale: Option<Language>) -> Option<String> {
+        let (mut decimal, mut grouping) = match locale {
+            Some(locale) if !NUMBER_DECIMAL_POINT_LANGUAGES.contains(&locale) => (',', '.'),
+            _ => ('.', ','),
+        };
+
+        // Swap separator roles if the decimal separator is repeated, as a number holds at \
+        //   most one decimal separator (eg. '1.000.000' in English is grouped with points)
+        if word.matches(decimal).nth(1).is_some() {
+            (decimal, grouping) = (grouping, decimal);
+        }
+
+        let mut normalized = String::with_capacity(word.len());
+        let (mut group_digits, mut is_decimal) = (None, false);
+
+        for character in word.chars() {
+            if character.is_ascii_digit() {
+                normalized.push(character);
+
+                group_digits = group_digits.map(|digits| digits + 1);
+
+                continue;
+            }
+
+            // Groups must be made of 3 digits, and cannot follow the decimal separator
+            if group_digits.is_some_and(|digits| digits != 3) {
+                return None;
+            }
+
+            if character == decimal && !is_decimal {
+                normalized.push('.');
+
+                group_digits = None;
+                is_decimal = true;
+            } else if (character == grouping || character == '\'') && !is_decimal {
+                group_digits = Some(0);
+            } else {
+                return None;
+            }
+        }
+
+        if group_digits.is_some_and(|digits| digits != 3) {
+            return None;
+        }
+
+        Some(normalized)
+    }
+
+    fn has_repeats(word: &str) -> bool {
+        let (mut previous, mut run_length) = (None, 0);
+
+        for character in word.chars() {
+            if previous == Some(character) {
+                run_length += 1;
+
+                if run_length >= TEXT_REPEATS_COLLAPSE_MIN_CHARS && Self::is_collapsible(character)
+                {
+                    return true;
+                }
+            } else {
+                previous = Some(character);
+                run_length = 1;
+            }
+        }
+
+        false
+    }
+
+    fn collapse_repeats(word: &str, collapse_length: usize) -> String {
+        let mut collapsed = String::with_capacity(word.len());
+        let mut characters = word.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            let mut run_length = 1;
+
+            while characters.peek() == Some(&character) {
+                characters.next();
+
+                run_length += 1;
+            }
+
+            // Only collapse long-enough runs of letters (runs of 2 letters are legit in many \
+            //   languages, eg. 'cool')
+            if run_length >= TEXT_REPEATS_COLLAPSE_MIN_CHARS && Self::is_collapsible(character) {
+                run_length = collapse_length.max(1);
+            }
+
+            for _ in 0..run_length {
+                collapsed.push(character);
+            }
+        }
+
+        collapsed
+    }
+
+    fn is_collapsible(character: char) -> bool {
+        // Only letters of alphabetic scripts are collapsed, as repeated characters are legit \
+        //   in other scripts (eg. '哈哈哈' in Chinese), as well as in numbers (eg. '1000')
+        if !character.is_alphabetic() {
+            return false;
+        }
+
+        let mut buffer = [0; 4];
+
+        matches!(
+            detect_script(character.encode_utf8(&mut buffer)),
+            Some(Script::Latin)
+                | Some(Script::Cyrillic)
+                | Some(Script::Greek)
+                | Some(Script::Armenian)
+                | Some(Script::Georgian)
+                | Some(Script::Arabic)
+                | Some(Script::Hebrew)
+        )
+    }
+
+    fn strip_clitics<'w>(word: Cow<'w, str>, elided: &[&str], attached: &[&str]) -> Cow<'w, str> {
+        let (mut start, mut end) = (0, word.len());
+
+        // Strip elided clitic before word (eg. 'l'home' becomes 'home')
+        if let Some(index) = word.find(APOSTROPHES_CLITICS) {
+            let next_index = index + word[index..].chars().next().map_or(0, char::len_utf8);
+
+            if next_index < end && elided.contains(&&word[..index]) {
+                start = next_index;
+            }
+        }
+
+        // Strip attached clitic after word (eg. 'porta'ls' becomes 'porta')
+        if let Some(index) = word[start..].rfind(APOSTROPHES_CLITICS) {
+            let index = start + index;
+            let next_index = index + word[index..].chars().next().map_or(0, char::len_utf8);
+
+            if index > start && attached.contains(&&word[next_index..]) {
+                end = index;
+            }
+        }
+
+        match word {
+            _ if start == 0 && end == word.len() => word,
+            Cow::Borrowed(word) => Cow::Borrowed(&word[start..end]),
+            Cow::Owned(word) => Cow::Owned(word[start..end].to_string()),
+        }
+    }
+
+    fn fold_width(word: &str) -> String {
+        let mut folded = String::with_capacity(word.len());
+
+        for character in word.chars() {
+            let character = Self::width_folded(character).unwrap_or(character);
+
+            // Compose voiced sound marks with their preceding Katakana (eg. 'ｶﾞ' is made of \
+            //   2 half-width characters, which become a single 'ガ' character)
+            let composed = match folded.chars().last() {
+                Some(previous) => Self::katakana_composed(previous, character),
+                None => None,
+            };
+
+            if let Some(composed) = composed {
+                folded.pop();
+                folded.push(composed);
+            } else {
+                folded.push(character);
+            }
+        }
+
+        folded
+    }
+
+    fn width_folded(character: char) -> Option<char> {
+        match character as u32 {
+            // Full-width ASCII characters (ie. from '！' to '～')
+            code @ 0xFF01..=0xFF5E => char::from_u32(code - 0xFEE0),
+            // Half-width Katakana characters and punctuation (ie. from '｡' to 'ﾟ')
+            code @ 0xFF61..=0xFF9F => KATAKANA_FULL_WIDTH.chars().nth((code - 0xFF61) as usize),
+            _ => None,
+        }
+    }
+
+    fn katakana_composed(base: char, mark: char) -> Option<char> {
+        let offset = match mark {
+            KATAKANA_MARK_VOICED if KATAKANA_VOICED_BASES.contains(base) => 1,
+            KATAKANA_MARK_VOICED if base == 'ウ' => 'ヴ' as u32 - 'ウ' as u32,
+            KATAKANA_MARK_VOICED if ('ワ'..='ヲ').contains(&base) => 'ヷ' as u32 - 'ワ' as u32,
+            KATAKANA_MARK_SEMI_VOICED if KATAKANA_SEMI_VOICED_BASES.contains(base) => 2,
+            _ => return None,
+        };
+
+        char::from_u32(base as u32 + offset)
+    }
+
+    fn fold_ligatures(word: &str) -> String {
+        let mut folded = String::with_capacity(word.len());
+
+        for character in word.chars() {
+            match Self::ligature_letters(character) {
+                Some(letters) => folded.push_str(letters),
+                None => folded.push(character),
+            }
+        }
+
+        folded
+    }
+
+    fn ligature_letters(character: char) -> Option<&'static str> {
+        // Notice: only lower-case ligatures are listed, as words are lower-cased beforehand.
+        match character {
+            '\u{FB00}' => Some("ff"),
+            '\u{FB01}' => Some("fi"),
+            '\u{FB02}' => Some("fl"),
+            '\u{FB03}' => Some("ffi"),
+            '\u{FB04}' => Some("ffl"),
+            '\u{FB05}' | '\u{FB06}' => Some("st"),
+            '\u{0133}' => Some("ij"),
+            '\u{01C6}' => Some("d\u{017E}"),
+            '\u{01C9}' => Some("lj"),
+            '\u{01CC}' => Some("nj"),
+            '\u{01F3}' => Some("dz"),
+            _ => None,
+        }
+    }
+
+    fn normalize_typography(word: &str) -> String {
+        let mut normalized = String::with_capacity(word.len());
+
+        for character in word.chars() {
+            match Self::typography_ascii(character) {
+                Some(ascii) => normalized.push_str(ascii),
+                None => normalized.push(character),
+            }
+        }
+
+        normalized
+    }
+
+    fn typography_ascii(character: char) -> Option<&'static str> {
+        match character {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => Some("'"),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => Some("\""),
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => Some("-"),
+            '\u{2026}' => Some("..."),
+            _ => None,
+        }
+    }
+
+    fn normalize_catalan_middle_dot(&self, word: &str) -> String {
+        let mut normalized = String::with_capacity(word.len());
+        let (mut characters, mut previous) = (word.chars().peekable(), None);
+
+        while let Some(character) = characters.next() {
+            // Only consider middle dots that are placed in-between two 'l', as other dots may \
+            //   have a different meaning (eg. a dot in an abbreviation)
+            if MIDDLE_DOTS_CATALAN.contains(&character)
+                && previous == Some('l')
+                && characters.peek() == Some(&'l')
+            {
+                // Either canonicalize the middle dot, or fold it (if enabled, in which case \
+                //   'paral·lel' and 'parallel' both map to the same word)
+                if !self.fold_catalan_middle_dot {
+                    normalized.push('\u{00B7}');
+                }
+            } else {
+                normalized.push(character);
+            }
+
+            previous = Some(character);
+        }
+
+        normalized
+    }
+}
+
+impl Default for TokenLexerOptions {
+    fn default() -> Self {
+        // Notice: options that change the words of a text (eg. filters, splitting or stripping \
+        //   rules) are disabled by default, as enabling them changes the terms of texts that \
+        //   were already indexed (which would then need to be re-indexed).
+        TokenLexerOptions {
+            case_mapping: TokenLexerCaseMapping::default(),
+            case: TokenLexerCase::default(),
+            retain_punctuation: Vec::new(),
+            max_word_bytes: TEXT_WORD_MAX_BYTES,
+            max_chars: None,
+            fold_catalan_middle_dot: false,
+            chinese_fallback: TokenLexerChineseFallback::default(),
+            chinese_variant: TokenLexerChineseVariant::default(),
+            japanese_fallback: TokenLexerJapaneseFallback::default(),
+            preserve_phrases: Vec::new(),
+            stopword_phrases: Vec::new(),
+            cjk_script_fallback: false,
+            normalize_typography: false,
+            max_repeats: 1,
+            redetect_every_words: None,
+            drop_modifiers_only: false,
+            split_digits: TokenLexerDigitsSplit::default(),
+            detect_densest_words: None,
+            split_clitics: false,
+            shingle_sizes: None,
+            shingle_skip_stopwords: true,
+            mixed_script_policy: None,
+            identifier_patterns: Vec::new(),
+            join_connectors: TokenLexerConnectors::default(),
+            collapse_repeats: None,
+            split_paths: false,
+            min_word_chars: TokenLexerMinWordChars::default(),
+            social_tags: false,
+            detect_max_chars: TEXT_LANG_TRUNCATE_OVER_CHARS,
+            mark_proper_nouns: false,
+            strip_joiners: false,
+            fold_ligatures: false,
+            segmentation: TokenLexerSegmentation::default(),
+            drop_punctuation_only: false,
+            reverse_words: None,
+            max_yields: None,
+            strip_emoji_modifiers: false,
+            fold_width: TokenLexerWidthFolding::default(),
+            undetected_sink: None,
+            normalize_numbers: false,
+            mark_undetermined: false,
+            allow_filter: None,
+            strip_controls: TokenLexerControls::Tokenizer,
+            stemmer: None,
+            stemming: TokenLexerStemming::Stemmed,
+            detect_present_scripts: false,
+            han_only: TokenLexerHanOnly::Detected,
+            keep_original_case: false,
+            keep_stopwords_under_words: None,
+            detect_overrides: Vec::new(),
+        }
+    }
+}
+
+impl TokenLexerCaseMapping {
+    fn lowercase<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        // Words that are already lower-case ASCII are left as-is (this is the most common \
+        //   case, and lower-casing them would be a no-op with both case mappings)
+        if Self::is_lowercased(word) {
+            return Cow::Borrowed(word);
+        }
+
+        Cow::Owned(match self {
+            TokenLexerCaseMapping::Full => word.to_lowercase(),
+            TokenLexerCaseMapping::Simple => {
+                // Map each character to its first lower-case character only, which is its \
+                //   simple (1:1) Unicode mapping. This guarantees that the lower-cased word \
+                //   holds as many characters as the original word.
+                // Notice: this trades correctness for determinism, as eg. 'İ' maps to 'i' \
+                //   instead of 'i̇', and a final 'Σ' maps to 'σ' instead of 'ς'.
+                word.chars().map(Self::lowercase_simple).collect()
+            }
+        })
+    }
+
+    fn lowercase_locale<'w>(&self, word: &'w str, locale: Option<Language>) -> Cow<'w, str> {
+        // Apply language-specific case mappings first (if any matches in word)
+        let mappings = locale.and_then(|locale| {
+            CASE_MAPPINGS_SPECIAL
+                .iter()
+                .find(|(mappings_locale, _)| *mappings_locale == locale)
+                .map(|(_, mappings)| *mappings)
+        });
+
+        let word = match mappings {
+            Some(mappings)
+                if word
+                    .chars()
+                    .any(|character| mappings.iter().any(|(from, _)| *from == character)) =>
+            {
+                let mut mapped = String::with_capacity(word.len());
+
+                for character in word.chars() {
+                    match mappings.iter().find(|(from, _)| *from == character) {
+                        Some((_, to)) => mapped.push_str(to),
+                        None => mapped.push(character),
+                    }
+                }
+
+                Cow::Owned(self.lowercase(&mapped).into_owned())
+            }
+            _ => self.lowercase(word),
+        };
+
+        // Fold Greek sigma to its final form? (at the end of words)
+        if locale == Some(Language::Greek) {
+            Self::fold_final_sigma(word)
+        } else {
+            word
+        }
+    }
+
+    fn fold_final_sigma(word: Cow<str>) -> Cow<str> {
+        // Greek lower-case sigma is written 'ς' at the end of a word, and 'σ' elsewhere. \
+        //   Full case mapping handles this when lower-casing a final 'Σ', but simple case \
+        //   mapping does not, and the wrong form is also commonly typed in lower-case text.
+        // Notice: always normalize to the positional form, so that 'ΟΔΟΣ', 'οδοσ' and 'οδος' \
+        //   all yield the same 'οδος' term.
+        let last_index = word.char_indices().last().map(|(index, _)| index);
+
+        let is_folded = word
+            .char_indices()
+            .all(|(index, character)| match character {
+                'σ' => Some(index) != last_index,
+                'ς' => Some(index) == last_index,
+                _ => true,
+            });
+
+        if is_folded {
+            return word;
+        }
+
+        Cow::Owned(
+            word.char_indices()
+                .map(|(index, character)| match character {
+                    'σ' | 'ς' if Some(index) == last_index => 'ς',
+                    'ς' => 'σ',
+                    _ => character,
+                })
+                .collect(),
+        )
+    }
+
+    fn uppercase(&self, word: &str) -> String {
+        match self {
+            TokenLexerCaseMapping::Full => word.to_uppercase(),
+            TokenLexerCaseMapping::Simple => word
+                .chars()
+                .map(|character| character.to_uppercase().next().unwrap_or(character))
+                .collect(),
+        }
+    }
+
+    fn lowercase_simple(character: char) -> char {
+        character.to_lowercase().next().unwrap_or(character)
+    }
+
+    fn is_lowercased(word: &str) -> bool {
+        word.bytes()
+            .all(|byte| byte.is_ascii() && !byte.is_ascii_uppercase())
+    }
+}
+
+impl TokenLexerMode {
+    pub fn from_query_lang(lang: Option<QueryGenericLang>) -> TokenLexerMode {
+        match lang {
+            Some(QueryGenericLang::Enabled(lang)) => {
+                // Cleanup with provided language
+                TokenLexerMode::NormalizeAndCleanup(Some(lang))
+            }
+            Some(QueryGenericLang::Disabled) => {
+                // Normalize only (language purposefully set to 'none')
+                TokenLexerMode::NormalizeOnly
+            }
+            Some(QueryGenericLang::Auto) => {
+                // Auto-detect language and cleanup (language purposefully set to 'auto')
+                TokenLexerMode::NormalizeAndCleanup(None)
+            }
+            None => {
+                // Auto-detect language and cleanup (this is the default behavior)
+                TokenLexerMode::NormalizeAndCleanup(None)
+            }
+        }
+    }
+
+    fn from_locale(locale: Option<Language>) -> TokenLexerMode {
+        // Hint an already-resolved locale, as to avoid detecting it again
+        match locale {
+            Some(locale) => TokenLexerMode::NormalizeAndCleanup(Some(locale)),
+            None => TokenLexerMode::NormalizeOnly,
+        }
+    }
+}
+
+impl<'a> Iterator for TokenLexer<'a> {
+    type Item = (String, StoreTermHashed);
+
+    // Guarantees provided by the lexer on the output: \
+    //   - Text is split per-word in a script-aware way \
+    //   - Words are normalized (ie. lower-case) \
+    //   - Gibberish words are removed (ie. words that may just be junk) \
+    //   - Stop-words are removed \
+    //   - Words are yielded in the order of their first occurrence in the text (ie. once \
+    //     stop-words and duplicates are removed), whichever the tokenizer
+    // Notice: if enabled, shingles of consecutive words are yielded after their last word, \
+    //   while proper noun markers, reversed words and expanded words are yielded after their \
+    //   word.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.has_reached_max_yields() {
+            return None;
+        }
+
+        let (word, term_hash) = self.next_yielded()?;
+
+        self.count_yielded();
+
+        Some((word.into_owned(), term_hash))
+    }
+}
+
+impl<'a> TokenLexer<'a> {
+    fn has_reached_max_yields(&self) -> bool {
+        // Stop yielding once the maximum number of yielded words is reached (if capped), as \
+        //   stacked expansions (eg. shingles, reversed and expanded words) could otherwise \
+        //   yield an unbounded number of words from a single document
+        self.options
+            .max_yields
+            .is_some_and(|max_yields| self.yields_count >= max_yields)
+    }
+
+    fn count_yielded(&mut self) {
+        self.yields_count += 1;
+
+        if Some(self.yields_count) == self.options.max_yields {
+            warn!(
+                "lexer reached maximum yielded words ({}), stopping: {}",
+                self.yields_count, self.text
+            );
+        }
+    }
+
+    fn next_yielded(&mut self) -> Option<(Cow<'a, str>, StoreTermHashed)> {
+        // Notice: words are yielded borrowed when they were not changed by normalization, as \
+        //   to avoid copying them if only their hash is needed.
+        loop {
+            if let Some((companion, term_hash)) = self.companions.pop_front() {
+                return Some((Cow::Owned(companion), term_hash));
+            }
+
+            let original = self.next_word()?;
+            let is_proper_noun = self.options.mark_proper_nouns && self.is_proper_noun(original);
+
+            let (word, term_hash, outcome) = self.decide(original);
+
+            if is_proper_noun {
+                self.mark_proper_noun(&word, &outcome);
+            }
+
+            if self.options.keep_original_case {
+                self.keep_original_case(original, &word, &outcome);
+            }
+
+            if let Some(max_chars) = self.options.reverse_words {
+                self.reverse(&word, &outcome, max_chars);
+            }
+
+            if self.expansion.is_some() {
+                self.expand(&word, &outcome);
+            }
+
+            if self.options.stemming == TokenLexerStemming::StemmedAndUnstemmed {
+                self.stem(&word, &outcome);
+            }
+
+            self.shingle(&word, &outcome);
+
+            if let (Some(term_hash), TokenDecisionOutcome::Yielded) = (term_hash, outcome) {
+                return Some((word, term_hash));
+            }
+        }
+    }
+}
+
+impl fmt::Display for TokenLexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            TokenLexerError::InvalidUtf8(valid_up_to) => {
+                write!(f, "invalid_utf8(at byte {})", valid_up_to)
+            }
+            TokenLexerError::UnsupportedLanguage(lang) => {
+                write!(f, "unsupported_language({})", lang.iso_code_639_3())
+            }
+            TokenLexerError::TokenizerFailed(lang) => {
+                write!(f, "tokenizer_failed({})", lang.iso_code_639_3())
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for TokenLexerHashes<'a> {
+    type Item = StoreTermHashed;
+
+    // Notice: this provides the same guarantees as the 'TokenLexer' iterator, and yields \
+    //   bit-identical hashes (companions included), though the words themselves are not \
+    //   yielded.
+    fn next(&mut self) -> Option<Self::Item> {
+        // Notice: the maximum number of yielded words is shared with the 'TokenLexer' \
+        //   iterator, thus both stop at the same word.
+        if self.lexer.has_reached_max_yields() {
+            return None;
+        }
+
+        let term_hash = self.next_hash()?;
+
+        self.lexer.count_yielded();
+
+        Some(term_hash)
+    }
+}
+
+impl<'a> TokenLexerHashes<'a> {
+    fn next_hash(&mut self) -> Option<StoreTermHashed> {
+        if !self.streamed {
+            return self.lexer.next_yielded().map(|(_, term_hash)| term_hash);
+        }
+
+        loop {
+            // Notice: companions may still be pending when streaming (eg. the undetermined \
+            //   marker, that gets pushed when the lexer is built).
+            if let Some((_, term_hash)) = self.lexer.companions.pop_front() {
+                return Some(term_hash);
+            }
+
+            let word = self.lexer.next_word()?;
+
+            // Hash word while lower-casing it if possible, which avoids allocating the \
+            //   lower-cased word
+            let decision = self.lexer.decide_streamed(word).unwrap_or_else(|| {
+                let (_, term_hash, outcome) = self.lexer.decide(word);
+
+                (term_hash, outcome)
+            });
+
+            if let (Some(term_hash), TokenDecisionOutcome::Yielded) = decision {
+                return Some(term_hash);
+            }
+        }
+    }
+}
+
+impl TokenLexerFootprint {
+    pub fn total_bytes(&self) -> usize {
+        self.term_bytes + self.hash_bytes
+    }
+}
+
+impl TokenLexerDetectionReport {
+    pub fn accuracy(&self) -> f64 {
+        if self.samples > 0 {
+            self.correct as f64 / self.samples as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+impl TokenLexerDetectionAccuracy {
+    pub fn precision(&self) -> Option<f64> {
+        // Share of texts detected as the language that were labeled with it (if any detected)
+        if self.detected > 0 {
+            Some(self.correct as f64 / self.detected as f64)
+        } else {
+            None
+        }
+    }
+
+    pub fn recall(&self) -> Option<f64> {
+        // Share of texts labeled with the language that were detected as it (if any labeled)
+        if self.expected > 0 {
+            Some(self.correct as f64 / self.expected as f64)
+        } else {
+            None
+        }
+    }
+}
+
+impl TokenLexerArena {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, StoreTermHashed)> {
+        self.tokens
+            .iter()
+            .map(move |(range, term_hash)| (&self.buffer[range.clone()], *term_hash))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl TokenLexerInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, term: &str) -> u32 {
+        // Assign the next identifier to terms that were never seen (identifiers are dense, \
+        //   and start from zero)
+        // Notice: terms are keyed by their string, thus colliding term hashes still get \
+        //   distinct identifiers.
+        if let Some(id) = self.ids.get(term) {
+            return *id;
+        }
+
+        let id = self.ids.len() as u32;
+
+        self.ids.insert(term.to_string(), id);
+
+        id
+    }
+
+    pub fn id(&self, term: &str) -> Option<u32> {
+        // Look up the identifier of a term, without assigning one (eg. for query terms)
+        self.ids.get(term).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl TokenLexerDetectionCache {
+    pub fn new() -> Self {
+        TokenLexerDetectionCache {
+            locales: Mutex::new(TokenLexerDetectionCacheLocales::default()),
+        }
+    }
+
+    fn detect(&self, text: &str, present_scripts: bool) -> Option<Language> {
+        if let Some(locale) = self
+            .locales
+            .lock()
+            .unwrap()
+            .for_scripts(present_scripts)
+            .get(text)
+        {
+            debug!("using cached locale for lexer text: {}", text);
+
+            LexerMetrics::count(LexerMetricsCounter::DetectionCacheHits);
+
+            return *locale;
+        }
+
+        // Notice: the cache is not locked while detecting, as to let other threads use the \
+        //   cache in the meantime (the same text may rarely be detected twice concurrently).
+        let locale = TokenLexerBuilder::detect_lang(text, present_scripts);
+
+        self.locales
+            .lock()
+            .unwrap()
+            .for_scripts(present_scripts)
+            .insert(text.to_string(), locale);
+
+        locale
+    }
+}
+
+impl TokenLexerDetectionCacheLocales {
+    fn for_scripts(&mut self, present_scripts: bool) -> &mut HashMap<String, Option<Language>> {
+        if present_scripts {
+            &mut self.present_scripts
+        } else {
+            &mut self.all_scripts
+        }
+    }
+}
+
+impl Default for TokenLexerDetectionCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> TokenLexerChunks<'a, I> {
+    fn lex_chunk(&mut self, chunk: &str) {
+        // Notice: chunks are split on whitespace only, as a word may straddle the edge \
+        //   between two chunks; the leading part of a chunk continues the word carried over \
+        //   from the previous chunk, and its trailing part may be continued by the next chunk. \
+        //   Thus, chunks that hold no whitespace (eg. Chinese text) are carried over as a whole.
+        let head_end = match chunk.find(char::is_whitespace) {
+            Some(head_end) => head_end,
+            None => {
+                self.push_carry(chunk);
+
+                return;
+            }
+        };
+
+        let tail_start = chunk
+            .char_indices()
+            .rev()
+            .find(|(_, character)| character.is_whitespace())
+            .map_or(head_end, |(index, character)| index + character.len_utf8());
+
+        self.carry.push_str(&chunk[..head_end]);
+
+        let carry = mem::take(&mut self.carry);
+
+        self.lex(&carry);
+        self.lex(&chunk[head_end..tail_start]);
+
+        self.push_carry(&chunk[tail_start..]);
+    }
+
+    fn push_carry(&mut self, text: &str) {
+        self.carry.push_str(text);
+
+        // Lex carried text once it gets longer than the longest word, as to hold at most a \
+        //   bounded part of texts that hold no whitespace (eg. Chinese text) in memory
+        // Notice: the carried text cannot be a single word anymore (it would not be yielded, \
+        //   as it is too long), thus it may be split there.
+        if self.carry.len() > self.options.max_word_bytes {
+            let carry = mem::take(&mut self.carry);
+
+            self.lex(&carry);
+        }
+    }
+
+    fn lex(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+
+        // Notice: the locale was detected once on the sample, thus it is hinted to the lexer, \
+        //   and de-duplication is shared across all lexed parts of the text. Shingles also \
+        //   continue from the previous part, as its words directly precede those of this part.
+        // Notice: stopwords are never kept on short texts, as parts of the text may be short \
+        //   even if the whole text is not.
+        let options = TokenLexerOptions {
+            keep_stopwords_under_words: None,
+            ..self.options.clone()
+        };
+
+        if let Ok(mut lexer) = TokenLexerBuilder::from_with_shared_yields(
+            TokenLexerMode::from_locale(self.locale),
+            text,
+            options,
+            &mut self.yields,
+        ) {
+            lexer.shingle_words = mem::take(&mut self.shingle_words);
+
+            self.words.extend(&mut lexer);
+
+            self.shingle_words = mem::take(&mut lexer.shingle_words);
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for TokenLexerChunks<'a, I> {
+    type Item = (String, StoreTermHashed);
+
+    // Notice: this provides the same guarantees as the 'TokenLexer' iterator, over the \
+    //   concatenation of all chunks. Though, as the text gets lexed in parts (split on the \
+    //   whitespace nearest to chunk edges), state that depends on preceding words restarts \
+    //   at each part: phrases (preserved or stopword ones) and proper nouns are not matched \
+    //   across parts, and stopwords are never kept on short texts. De-duplication, shingles, \
+    //   the undetermined marker and the maximum number of yielded words apply to the whole \
+    //   text. Parts are at most as long as the longest word, unless they hold whitespace.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(max_yields) = self.options.max_yields {
+            if self.yields_count >= max_yields {
+                return None;
+            }
+        }
+
+        loop {
+            if let Some(word) = self.words.pop_front() {
+                self.yields_count += 1;
+
+                return Some(word);
+            }
+
+            match self.sampled.pop_front().or_else(|| self.chunks.next()) {
+                Some(chunk) => self.lex_chunk(chunk),
+                None if !self.carry.is_empty() => {
+                    let carry = mem::take(&mut self.carry);
+
+                    self.lex(&carry);
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for TokenLexerOffsets<'a> {
+    type Item = (String, StoreTermHashed, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Notice: the maximum number of yielded words is shared with the 'TokenLexer' \
+        //   iterator, thus both stop at the same word.
+        if self.lexer.has_reached_max_yields() {
+            return None;
+        }
+
+        while let Some(word) = self.lexer.next_word() {
+            if let (normalized, Some(term_hash), TokenDecisionOutcome::Yielded) =
+                self.lexer.decide(word)
+            {
+                self.lexer.count_yielded();
+
+                // Notice: all tokenizers yield words that are slices of the text, thus the byte \
+                //   range can be computed from the position of the slice in the text. This is \
+                //   correct for characters of any UTF-8 length (eg. astral characters, that \
+                //   take 4 bytes).
+                let start = match self.lexer.words {
+                    TokenLexerWords::Tokens(_) => 0,
+                    _ => word.as_ptr() as usize - self.lexer.text.as_ptr() as usize,
+                };
+
+                return Some((
+                    normalized.into_owned(),
+                    term_hash,
+                    start..(start + word.len()),
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, 'i> Iterator for TokenLexerInterned<'a, 'i> {
+    type Item = (String, StoreTermHashed, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (word, term_hash) = self.lexer.next()?;
+        let id = self.interner.intern(&word);
+
+        Some((word, term_hash, id))
+    }
+}
+
+impl<'a> TokenLexerWords<'a> {
+    // Notice: options are only used by the Chinese and Japanese tokenizers, if enabled
+    #[cfg_attr(
+        not(any(feature = "tokenizer-chinese", feature = "tokenizer-japanese")),
+        allow(unused_variables)
+    )]
+    fn from_tokenizer(
+        tokenizer: TokenLexerTokenizer,
+        text: &'a str,
+        options: &TokenLexerOptions,
+    ) -> Result<Self, TokenLexerError> {
+        let words = match tokenizer {
+            TokenLexerTokenizer::UAX29 => TokenLexerWords::UAX29(text.unicode_words()),
+            TokenLexerTokenizer::Syllables => {
+                TokenLexerWords::Syllables(text.split(TokenLexer::is_syllable_separator))
+            }
+            #[cfg(feature = "tokenizer-khmer")]
+            TokenLexerTokenizer::Khmer => TokenLexerWords::Khmer(Self::cut_khmer(text).into_iter()),
+            #[cfg(not(feature = "tokenizer-chinese"))]
+            TokenLexerTokenizer::HanBigrams => {
+                TokenLexerWords::HanBigrams(TokenLexerHanBigrams::new(text))
+            }
+            #[cfg(feature = "tokenizer-whitespace")]
+            TokenLexerTokenizer::Whitespace => {
+                TokenLexerWords::Whitespace(TokenLexerWhitespaceWords::new(text))
+            }
+            #[cfg(feature = "tokenizer-chinese")]
+            TokenLexerTokenizer::JieBa => {
+                TokenLexerWords::JieBa(Self::cut_chinese(text, options.chinese_variant).into_iter())
+            }
+            #[cfg(feature = "tokenizer-japanese")]
+            TokenLexerTokenizer::Lindera => match TOKENIZER_LINDERA.tokenize(text) {
+                Ok(tokens) => TokenLexerWords::Lindera(tokens.into_iter()),
+                Err(err) => {
+                    if options.japanese_fallback == TokenLexerJapaneseFallback::Reject {
+                        warn!("unable to tokenize japanese, rejecting: {}", err);
+
+                        return Err(TokenLexerError::TokenizerFailed(Language::Japanese));
+                    }
+
+                    warn!("unable to tokenize japanese, falling back: {}", err);
+
+                    TokenLexerWords::UAX29(text.unicode_words())
+                }
+            },
+        };
+
+        Ok(words)
+    }
+
+    #[cfg(feature = "tokenizer-khmer")]
+    fn cut_khmer(text: &'a str) -> Vec<&'a str> {
+        // Segment Khmer text into words, using the ICU dictionary of Khmer words (segments \
+        //   that hold no word, eg. spaces and punctuation, are skipped)
+        // Notice: the word-like flag of segments is not used, as ICU does not set it on \
+        //   some Khmer words.
+        TOKENIZER_KHMER.with(|segmenter| {
+            let mut words = Vec::new();
+            let mut start = 0;
+
+            for end in segmenter.segment_str(text) {
+                let word = &text[start..end];
+
+                if word.chars().any(char::is_alphanumeric) {
+                    words.push(word);
+                }
+
+                start = end;
+            }
+
+            words
+        })
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    fn cut_chinese(text: &'a str, variant: TokenLexerChineseVariant) -> Vec<&'a str> {
+        // Segment the Simplified form of the text? (if a variant is set, as Traditional words \
+        //   are unknown to the tokenizer dictionary, and would get split into characters)
+        // Notice: words are cut from the original text, which requires that the conversion \
+        //   maps each character to a single character (this is the case for most texts, \
+        //   otherwise the original text is segmented as-is).
+        if variant != TokenLexerChineseVariant::Unchanged {
+            let simplified = zhconv::zhconv(text, zhconv::Variant::ZhHans);
+
+            if simplified != text && simplified.chars().count() == text.chars().count() {
+                let bounds = text
+                    .char_indices()
+                    .map(|(index, _)| index)
+                    .chain(Some(text.len()))
+                    .collect::<Vec<_>>();
+
+                let mut start = 0;
+
+                return TOKENIZER_JIEBA
+                    .cut(&simplified, false)
+                    .into_iter()
+                    .map(|word| {
+                        let end = start + word.chars().count();
+                        let word = &text[bounds[start]..bounds[end]];
+
+                        start = end;
+
+                        word
+                    })
+                    .collect();
+            }
+        }
+
+        TOKENIZER_JIEBA.cut(text, false)
+    }
+}
+
+impl<'a> Iterator for TokenLexerWords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            TokenLexerWords::UAX29(token) => token.next(),
+            TokenLexerWords::UAX29Merged(token) => token.next(),
+            TokenLexerWords::Paths(token) => token.next(),
+            TokenLexerWords::AlphanumericRuns(token) => token.find(|word| !word.is_empty()),
+            TokenLexerWords::Syllables(token) => {
+                token.find(|word| word.chars().any(char::is_alphanumeric))
+            }
+
+            #[cfg(not(feature = "tokenizer-chinese"))]
+            TokenLexerWords::HanBigrams(token) => token.next(),
+
+            #[cfg(feature = "tokenizer-whitespace")]
+            TokenLexerWords::Whitespace(token) => token.next(),
+
+            TokenLexerWords::Tokens(token) => token.next().copied(),
+            TokenLexerWords::Single(token) => token.next(),
+
+            #[cfg(feature = "tokenizer-chinese")]
+            TokenLexerWords::JieBa(token) => token.next(),
 
             #[cfg(feature = "tokenizer-japanese")]
             TokenLexerWords::Lindera(token) => match token.next() {
                 Some(inner) => Some(inner.text),
                 None => None,
             },
+
+            #[cfg(feature = "tokenizer-khmer")]
+            TokenLexerWords::Khmer(token) => token.next(),
+        }
+    }
+}
+
+impl<'a> TokenLexerYields<'a> {
+    fn insert(&mut self, term_hash: StoreTermHashed) -> bool {
+        match self {
+            TokenLexerYields::Owned(yields) => yields.insert(term_hash),
+            TokenLexerYields::Shared(yields) => yields.insert(term_hash),
+            TokenLexerYields::Counted(counts, max_repeats) => {
+                let count = counts.entry(term_hash).or_insert(0);
+
+                if *count < *max_repeats {
+                    *count += 1;
+
+                    true
+                } else {
+                    false
+                }
+            }
+            TokenLexerYields::Untracked => true,
+        }
+    }
+}
+
+impl<'a> TokenLexerMergedWords<'a> {
+    fn new(
+        text: &'a str,
+        retain: Vec<char>,
+        prefixes: Vec<char>,
+        controls: TokenLexerControls,
+    ) -> Self {
+        TokenLexerMergedWords {
+            text,
+            bounds: text.split_word_bound_indices().peekable(),
+            retain,
+            prefixes,
+            controls,
+        }
+    }
+
+    fn is_word(segment: &str) -> bool {
+        // Notice: this is the same filter that 'unicode_words()' applies to word bounds
+        segment.chars().any(char::is_alphanumeric)
+    }
+
+    fn is_retained(&self, segment: &str) -> bool {
+        // Notice: stripped control characters are retained, as they get removed from words \
+        //   once normalized (thus words are merged across them).
+        segment.chars().all(|character| {
+            self.retain.contains(&character) || self.controls.is_stripped(character)
+        })
+    }
+
+    fn is_prefix(&mut self, start: usize, segment: &str) -> bool {
+        // Prefixes must lead a word, and must not follow a word (eg. the '@' in an email \
+        //   address is not a mention prefix)
+        let mut characters = segment.chars();
+
+        match (characters.next(), characters.next()) {
+            (Some(character), None) if self.prefixes.contains(&character) => {
+                self.text[..start]
+                    .chars()
+                    .next_back()
+                    .is_none_or(char::is_whitespace)
+                    && self
+                        .bounds
+                        .peek()
+                        .is_some_and(|&(_, next_segment)| Self::is_word(next_segment))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Iterator for TokenLexerMergedWords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((start, segment)) = self.bounds.next() {
+            let mut has_word = Self::is_word(segment);
+
+            if has_word || self.is_retained(segment) || self.is_prefix(start, segment) {
+                let mut end = start + segment.len();
+
+                // Merge all following contiguous word and retained punctuation bounds, as \
+                //   retained punctuation should not trigger a word break (eg. 'C++')
+                while let Some(&(next_start, next_segment)) = self.bounds.peek() {
+                    let next_is_word = Self::is_word(next_segment);
+
+                    if next_is_word || self.is_retained(next_segment) {
+                        has_word = has_word || next_is_word;
+                        end = next_start + next_segment.len();
+
+                        self.bounds.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                // Retained punctuation must be adjacent to a word to be yielded
+                if has_word {
+                    return Some(&self.text[start..end]);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> TokenLexerPathWords<'a> {
+    fn new(text: &'a str) -> Self {
+        TokenLexerPathWords {
+            chunks: text.split_whitespace(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn split_path(&mut self, path: &'a str) {
+        // Notice: empty components are skipped (eg. leading separator)
+        let mut components = path
+            .split(PATH_SEPARATORS)
+            .filter(|component| !component.is_empty())
+            .peekable();
+
+        while let Some(component) = components.next() {
+            if components.peek().is_some() {
+                // Directory component
+                self.pending.push_back(component);
+            } else {
+                // Base name component (yield the full base name, then its stem and extension \
+                //   if any, eg. 'app.2019.log' yields 'app.2019.log', 'app.2019' and 'log')
+                self.pending.push_back(component);
+
+                if let Some(dot_index) = component.rfind('.') {
+                    let (stem, extension) = (&component[..dot_index], &component[dot_index + 1..]);
+
+                    if !stem.is_empty() && !extension.is_empty() {
+                        self.pending.push_back(stem);
+                        self.pending.push_back(extension);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for TokenLexerPathWords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(word) = self.pending.pop_front() {
+                return Some(word);
+            }
+
+            let chunk = self.chunks.next()?;
+
+            if chunk.contains(PATH_SEPARATORS) {
+                self.split_path(chunk);
+            } else {
+                // Not a path, tokenize as usual
+                self.pending.extend(chunk.unicode_words());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokenizer-whitespace")]
+impl<'a> TokenLexerWhitespaceWords<'a> {
+    fn new(text: &'a str) -> Self {
+        TokenLexerWhitespaceWords {
+            words: text.split_whitespace(),
+        }
+    }
+}
+
+#[cfg(feature = "tokenizer-whitespace")]
+impl<'a> Iterator for TokenLexerWhitespaceWords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Split on whitespace only, which is faster than UAX29 though less accurate (eg. \
+        //   punctuation within words is kept, as in 'e-mail'); punctuation surrounding words \
+        //   gets trimmed (eg. 'addresses!' gives 'addresses')
+        for word in self.words.by_ref() {
+            let word = word.trim_matches(|character: char| !character.is_alphanumeric());
+
+            if !word.is_empty() {
+                return Some(word);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(not(feature = "tokenizer-chinese"))]
+impl<'a> TokenLexerHanBigrams<'a> {
+    fn new(text: &'a str) -> Self {
+        TokenLexerHanBigrams {
+            text,
+            bounds: text.split_word_bound_indices().peekable(),
+            covered_until: 0,
+        }
+    }
+
+    fn is_ideograph(segment: &str) -> bool {
+        let mut characters = segment.chars();
+
+        // Notice: UAX29 yields each ideograph as its own word bound
+        match (characters.next(), characters.next()) {
+            (Some(character), None) => matches!(
+                character as u32,
+                0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x3134F
+            ),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(not(feature = "tokenizer-chinese"))]
+impl<'a> Iterator for TokenLexerHanBigrams<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((start, segment)) = self.bounds.next() {
+            if Self::is_ideograph(segment) {
+                // Pair the ideograph with the following one (if any), producing overlapping \
+                //   bigrams (eg. '北京华' gives '北京' and '京华')
+                if let Some(&(next_start, next_segment)) = self.bounds.peek() {
+                    if Self::is_ideograph(next_segment) {
+                        self.covered_until = next_start + next_segment.len();
+
+                        return Some(&self.text[start..self.covered_until]);
+                    }
+                }
+
+                // Lone ideograph, which is yielded as-is if not already part of a bigram
+                if start >= self.covered_until {
+                    return Some(segment);
+                }
+            } else if TokenLexerMergedWords::is_word(segment) {
+                return Some(segment);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "lexer-metrics")]
+    use crate::lexer::metrics::metrics_snapshot;
+
+    #[cfg(not(feature = "detector-lingua"))]
+    #[test]
+    fn it_cleans_token_without_detector() {
+        let text = "The quick brown fox jumps over the lazy dog!";
+
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), text).unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog"]
+        );
+
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::from_query_lang(QueryGenericLang::from_value("eng")),
+            text,
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::English));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("brown".to_string(), 1268820067))
+        );
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(token_cleaner.next(), Some(("jumps".to_string(), 633865164)));
+        assert_eq!(token_cleaner.next(), Some(("lazy".to_string(), 4130433347)));
+        assert_eq!(token_cleaner.next(), Some(("dog".to_string(), 2044924251)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_cleans_token_english() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "The quick brown fox jumps over the lazy dog!",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::English));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("brown".to_string(), 1268820067))
+        );
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(token_cleaner.next(), Some(("jumps".to_string(), 633865164)));
+        assert_eq!(token_cleaner.next(), Some(("lazy".to_string(), 4130433347)));
+        assert_eq!(token_cleaner.next(), Some(("dog".to_string(), 2044924251)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_cleans_token_french() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "Le vif renard brun saute par dessus le chien paresseux.",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::French));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("renard".to_string(), 1635186311))
+        );
+        assert_eq!(token_cleaner.next(), Some(("brun".to_string(), 2763604928)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("saute".to_string(), 1918158211))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("chien".to_string(), 2177818351))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("paresseux".to_string(), 1678693110))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_cleans_token_chinese_jieba() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "我们中出了一个叛徒",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+        assert_eq!(token_cleaner.next(), Some(("出".to_string(), 241978070)));
+        assert_eq!(token_cleaner.next(), Some(("一个".to_string(), 2596274530)));
+        assert_eq!(token_cleaner.next(), Some(("叛徒".to_string(), 3244183759)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_for_field() {
+        let mut policies = TokenLexerFieldPolicies::new();
+
+        policies.insert(
+            "tags".to_string(),
+            TokenLexerFieldPolicy {
+                mode: Some(TokenLexerMode::NormalizeOnly),
+                options: TokenLexerOptions {
+                    segmentation: TokenLexerSegmentation::AlphanumericRuns,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        policies.insert(
+            "body".to_string(),
+            TokenLexerFieldPolicy {
+                tokenizer: Some(TokenLexerTokenizer::UAX29),
+                ..Default::default()
+            },
+        );
+
+        let text = "The node.js runtime";
+
+        let lex = |field| {
+            TokenLexerBuilder::from_for_field(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                &policies,
+                field,
+                text,
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(lex("tags"), vec!["the", "node", "js", "runtime"]);
+        assert_eq!(lex("body"), vec!["node.js", "runtime"]);
+        assert_eq!(lex("title"), lex("body"));
+    }
+
+    #[test]
+    fn it_tokenizes_uax29() {
+        let token_cleaner = TokenLexerBuilder::from_tokenizer(
+            TokenLexerMode::NormalizeOnly,
+            "The quick brown fox's 北京",
+            TokenLexerTokenizer::UAX29,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["the", "quick", "brown", "fox's", "北", "京"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_tokenizes_jieba() {
+        let token_cleaner = TokenLexerBuilder::from_tokenizer(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "我们中出了一个叛徒",
+            TokenLexerTokenizer::JieBa,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["出", "一个", "叛徒"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_cleans_token_chinese_function_words() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "他已经没有钱了",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["钱"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-khmer")]
+    #[test]
+    fn it_tokenizes_khmer() {
+        let text = "ខ្ញុំស្រលាញ់ប្រទេសកម្ពុជា។";
+
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), text).unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["ស្រលាញ់"]
+        );
+
+        assert_eq!(
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, text)
+                .unwrap()
+                .map(|value| value.0)
+                .collect::<Vec<_>>(),
+            vec!["ខ្ញុំ", "ស្រលាញ់", "ប្រទេស", "កម្ពុជា"]
+        );
+
+        assert!(TokenLexer::is_khmer(text));
+        assert!(!TokenLexer::is_khmer("The Khmer word for country is ប្រទេស"));
+    }
+
+    #[test]
+    fn it_tokenizes_syllables() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "བཀྲ་ཤིས་བདེ་ལེགས། ཤིས་",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["བཀྲ", "ཤིས", "བདེ", "ལེགས"]
+        );
+
+        assert!(TokenLexer::is_syllable_delimited("བཀྲ་ཤིས་བདེ་ལེགས།"));
+        assert!(!TokenLexer::is_syllable_delimited(
+            "The Tibetan greeting is བཀྲ་ཤིས"
+        ));
+        assert!(!TokenLexer::is_syllable_delimited("Hello world"));
+    }
+
+    #[test]
+    fn it_segments_token() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The quick fox, the QUICK fox!",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.segments(),
+            vec!["The", "quick", "fox", "the", "QUICK", "fox"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_segments_token_chinese() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "我们中出了一个叛徒",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.segments(),
+            vec!["我们", "中", "出", "了", "一个", "叛徒"]
+        );
+    }
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    #[test]
+    fn it_tokenizes_han_bigrams() {
+        let token_cleaner = TokenLexerBuilder::from_tokenizer(
+            TokenLexerMode::NormalizeOnly,
+            "北京华",
+            TokenLexerTokenizer::HanBigrams,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["北京", "京华"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-whitespace")]
+    #[test]
+    fn it_tokenizes_whitespace() {
+        let token_cleaner = TokenLexerBuilder::from_tokenizer(
+            TokenLexerMode::NormalizeOnly,
+            "Send e-mail addresses!",
+            TokenLexerTokenizer::Whitespace,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["send", "e-mail", "addresses"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_tokenizes_lindera() {
+        let token_cleaner = TokenLexerBuilder::from_tokenizer(
+            TokenLexerMode::NormalizeOnly,
+            "関西国際空港",
+            TokenLexerTokenizer::Lindera,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["関西", "国際", "空港"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_cleans_token_chinese_variant() {
+        let lex = |text, chinese_variant| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+                text,
+                TokenLexerOptions {
+                    chinese_variant,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>()
+        };
+
+        assert_ne!(
+            lex("我們中出了一個叛徒", TokenLexerChineseVariant::Unchanged),
+            lex("我们中出了一个叛徒", TokenLexerChineseVariant::Unchanged)
+        );
+
+        for text in ["我们中出了一个叛徒", "我們中出了一個叛徒"] {
+            assert_eq!(
+                lex(text, TokenLexerChineseVariant::Simplified),
+                vec!["出", "一个", "叛徒"]
+            );
+            assert_eq!(
+                lex(text, TokenLexerChineseVariant::Traditional),
+                vec!["出", "一個", "叛徒"]
+            );
+        }
+    }
+
+    #[cfg(all(feature = "detector-lingua", not(feature = "tokenizer-chinese")))]
+    #[test]
+    fn it_cleans_token_chinese_naive() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "快狐跨懒狗快狐跨懒狗",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+        assert_eq!(token_cleaner.next(), Some(("快".to_string(), 126546256)));
+        assert_eq!(token_cleaner.next(), Some(("狐".to_string(), 2879689662)));
+        assert_eq!(token_cleaner.next(), Some(("跨".to_string(), 2913342670)));
+        assert_eq!(token_cleaner.next(), Some(("懒".to_string(), 3199935961)));
+        assert_eq!(token_cleaner.next(), Some(("狗".to_string(), 3360772096)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_cleans_token_han_only() {
+        let lex = |han_only| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                "私 的 学生",
+                TokenLexerOptions {
+                    han_only,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let token_cleaner = lex(TokenLexerHanOnly::Chinese);
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+
+        let words = token_cleaner.map(|value| value.0).collect::<Vec<_>>();
+
+        assert!(words.contains(&"私".to_string()));
+        assert!(!words.contains(&"的".to_string()));
+
+        let token_cleaner = lex(TokenLexerHanOnly::Japanese);
+
+        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
+
+        let words = token_cleaner.map(|value| value.0).collect::<Vec<_>>();
+
+        assert!(!words.contains(&"私".to_string()));
+        assert!(words.contains(&"的".to_string()));
+
+        assert!(TokenLexerHanOnly::is_han_only("私 的 学生"));
+        assert!(!TokenLexerHanOnly::is_han_only("私は学生です"));
+        assert!(!TokenLexerHanOnly::is_han_only("iPhone 手机"));
+        assert!(!TokenLexerHanOnly::is_han_only("2019"));
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_cleans_token_japanese_lindera_product() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "関西国際空港限定トートバッグ",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
+        assert_eq!(token_cleaner.next(), Some(("関西".to_string(), 1283572620)));
+        assert_eq!(token_cleaner.next(), Some(("国際".to_string(), 2132457693)));
+        assert_eq!(token_cleaner.next(), Some(("空港".to_string(), 865668138)));
+        assert_eq!(token_cleaner.next(), Some(("限定".to_string(), 3708465176)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("トート".to_string(), 881444746))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("バッグ".to_string(), 3515727814))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_cleans_token_japanese_lindera_food() {
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "𠮷野家").unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "ヱビスビール")
+                .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_cleans_token_japanese_lindera_food_fallback() {
+        let options = TokenLexerOptions {
+            cjk_script_fallback: true,
+            ..Default::default()
+        };
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "𠮷野家",
+            options.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "ヱビスビール",
+            options,
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("ヱビス".to_string(), 1696836208))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("ビール".to_string(), 3421909800))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_cleans_token_japanese_lindera_sentence() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "𠮷野家でヱビスビールを飲んだ",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
+        assert_eq!(token_cleaner.next(), Some(("𠮷".to_string(), 2866455824)));
+        assert_eq!(token_cleaner.next(), Some(("野家".to_string(), 1324395598)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("ヱビス".to_string(), 1696836208))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("ビール".to_string(), 3421909800))
+        );
+        assert_eq!(token_cleaner.next(), Some(("飲ん".to_string(), 3196735184)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_no_lang_non_linguistic() {
+        assert_eq!(
+            TokenLexerBuilder::detect_lang("2019-11-05 12:00:00", false),
+            None
+        );
+        assert_eq!(TokenLexerBuilder::detect_lang("--- === ---", false), None);
+        assert_eq!(TokenLexerBuilder::detect_lang("", false), None);
+
+        assert_eq!(
+            TokenLexerBuilder::detect_lang(
+                "The quick brown fox jumps over the lazy dog in 2019!",
+                false
+            ),
+            Some(Language::English)
+        );
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_cleans_token_lang_redetected() {
+        let text = "The cat is sleeping on the table y el perro duerme en la cocina";
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            text,
+            TokenLexerOptions {
+                redetect_every_words: Some(7),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["cat", "sleeping", "table", "perro", "duerme", "cocina"]
+        );
+
+        // Without re-detection, the whole text is cleaned as a single language
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+        )
+        .unwrap();
+
+        assert!(token_cleaner.map(|value| value.0).any(|word| word == "el"));
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_cleans_token_lang_priority() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanupPriority(vec![Language::Spanish, Language::English]),
+            "The quick brown fox jumps over the lazy dog!",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::English));
+
+        // Ambiguous text (no language can be told apart)
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanupPriority(vec![Language::English, Language::Spanish]),
+            "2019",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::English));
+
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanupPriority(vec![Language::Spanish, Language::English]),
+            "2019",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Spanish));
+    }
+
+    #[cfg(not(feature = "tokenizer-whitespace"))]
+    #[test]
+    fn it_cleans_token_segmenter_uax29() {
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "e-mail addresses!").unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["e", "mail", "addresses"]
+        );
+    }
+
+    #[cfg(feature = "tokenizer-whitespace")]
+    #[test]
+    fn it_cleans_token_segmenter_whitespace() {
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "e-mail addresses!").unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["e-mail", "addresses"]
+        );
+        assert_eq!(
+            "e-mail addresses!".unicode_words().collect::<Vec<_>>(),
+            vec!["e", "mail", "addresses"]
+        );
+    }
+
+    // Notice: the detector guesses a language for short CJK texts, thus the fallback is only \
+    //   used when no detector is compiled in.
+    #[cfg(not(feature = "detector-lingua"))]
+    #[test]
+    fn it_cleans_token_cjk_script_fallback() {
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "ヱビスビール")
+                .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "ヱビスビール",
+            TokenLexerOptions {
+                cjk_script_fallback: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
+    }
+
+    #[cfg(feature = "lexer-metrics")]
+    #[test]
+    fn it_counts_metrics_english() {
+        let before = metrics_snapshot();
+
+        TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "The quick brown fox jumps over the lazy dog!",
+        )
+        .unwrap()
+        .for_each(drop);
+
+        // Notice: counters are global and tests run in parallel, thus other tests may \
+        //   increment them at the same time.
+        let after = metrics_snapshot();
+
+        assert!(after.documents_tokenized > before.documents_tokenized);
+        assert!(after.detection_calls > before.detection_calls);
+        assert!(after.tokens_yielded >= before.tokens_yielded + 6);
+        assert!(after.stopwords_dropped >= before.stopwords_dropped + 3);
+    }
+
+    #[test]
+    fn it_cleans_token_emojis() {
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "🚀 🙋‍♂️🙋‍♂️🙋‍♂️")
+                .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_blank() {
+        for text in ["", "   ", " \t\n "] {
+            let mut token_cleaner =
+                TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), text).unwrap();
+
+            assert!(token_cleaner.is_blank());
+            assert_eq!(token_cleaner.next(), None);
+        }
+
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "🚀 🙋‍♂️🙋‍♂️🙋‍♂️")
+                .unwrap();
+
+        assert!(!token_cleaner.is_blank());
+        assert_eq!(token_cleaner.next(), None);
+
+        assert!(TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeOnly,
+            &[" ", ""],
+            TokenLexerOptions::default()
+        )
+        .unwrap()
+        .is_blank());
+        assert!(!TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeOnly,
+            &[" ", "tag"],
+            TokenLexerOptions::default()
+        )
+        .unwrap()
+        .is_blank());
+    }
+
+    #[test]
+    fn it_cleans_token_width_folded() {
+        let options = TokenLexerOptions::default();
+
+        assert_eq!(
+            options.normalize_word("ＡＢＣ１２３", Some(Language::Japanese)),
+            "abc123"
+        );
+        assert_eq!(
+            options.normalize_word("ｶﾀｶﾅ", Some(Language::Japanese)),
+            "カタカナ"
+        );
+        assert_eq!(
+            options.normalize_word("ｶﾞｯﾂﾎﾟｰｽﾞ", Some(Language::Japanese)),
+            "ガッツポーズ"
+        );
+        assert_eq!(
+            options.normalize_word("ＡＢＣ", Some(Language::English)),
+            "ａｂｃ"
+        );
+        assert_eq!(options.normalize_word("ＡＢＣ", None), "ａｂｃ");
+
+        let options = TokenLexerOptions {
+            fold_width: TokenLexerWidthFolding::Enabled,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.normalize_word("ＡＢＣ", Some(Language::English)),
+            "abc"
+        );
+
+        let options = TokenLexerOptions {
+            fold_width: TokenLexerWidthFolding::Disabled,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.normalize_word("ｶﾀｶﾅ", Some(Language::Japanese)),
+            "ｶﾀｶﾅ"
+        );
+    }
+
+    #[test]
+    fn it_detects_lang_overrides() {
+        let options = TokenLexerOptions {
+            detect_overrides: vec![
+                (Regex::new(r"SKU-").unwrap(), Language::English),
+                (Regex::new(r"SKU").unwrap(), Language::French),
+            ],
+            ..Default::default()
+        };
+
+        let lexer = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "SKU-1234 der schnelle braune Fuchs springt über den faulen Hund",
+            options.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(lexer.locale, Some(Language::English));
+
+        assert_eq!(
+            TokenLexerBuilder::detect_locale(
+                &TokenLexerMode::NormalizeAndCleanup(None),
+                "SKU 1234 the quick brown fox",
+                &options,
+                None
+            )
+            .unwrap(),
+            Some(Language::French)
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_keep_stopwords_short() {
+        let lex = |text| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                text,
+                TokenLexerOptions {
+                    keep_stopwords_under_words: Some(3),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(lex("The Who"), vec!["the", "who"]);
+        assert_eq!(lex("The Who is touring"), vec!["touring"]);
+        assert_eq!(
+            TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                "The Who",
+            )
+            .unwrap()
+            .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_original_case() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "NASA fox, nasa NASA",
+            TokenLexerOptions {
+                keep_original_case: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["nasa", "NASA", "fox"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_stemmed() {
+        let stemmer: TokenLexerStemmer = Arc::new(|word: &str, locale: Option<Language>| {
+            match (locale, word.strip_suffix("ning")) {
+                (Some(Language::English), Some(stem)) => Some(stem.to_string()),
+                _ => None,
+            }
+        });
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Running dogs",
+            TokenLexerOptions {
+                stemmer: Some(stemmer.clone()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["run", "dogs"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Running dogs, run!",
+            TokenLexerOptions {
+                stemmer: Some(stemmer),
+                stemming: TokenLexerStemming::StemmedAndUnstemmed,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["running", "run", "dogs"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_controls() {
+        let text = "Scanned invo\u{0007}ice from the acc\u{0085}ount\u{E000}ing\tdesk\u{009B}";
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+            TokenLexerOptions {
+                strip_controls: TokenLexerControls::Strip,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["scanned", "invoice", "acc", "ount", "ing", "desk"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+            TokenLexerOptions {
+                strip_controls: TokenLexerControls::StripWithPrivateUse,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["scanned", "invoice", "acc", "ounting", "desk"]
+        );
+
+        let options = TokenLexerOptions {
+            strip_controls: TokenLexerControls::Strip,
+            ..Default::default()
+        };
+
+        assert_eq!(options.normalize_word("INVO\u{0000}ICE", None), "invoice");
+        assert_eq!(
+            TokenLexerOptions::default().normalize_word("invo\u{0000}ice", None),
+            "invo\u{0000}ice"
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_case_special() {
+        for case_mapping in [TokenLexerCaseMapping::Full, TokenLexerCaseMapping::Simple] {
+            let options = TokenLexerOptions {
+                case_mapping,
+                ..Default::default()
+            };
+
+            assert_eq!(
+                options.normalize_word("STRASSE", Some(Language::German)),
+                "strasse"
+            );
+            assert_eq!(
+                options.normalize_word("Straße", Some(Language::German)),
+                "strasse"
+            );
+            assert_eq!(
+                options.normalize_word("STRAẞE", Some(Language::German)),
+                "strasse"
+            );
+            assert_eq!(
+                options.normalize_word("ΟΔΟΣ", Some(Language::Greek)),
+                "οδος"
+            );
+            assert_eq!(
+                options.normalize_word("οδοσ", Some(Language::Greek)),
+                "οδος"
+            );
+            assert_eq!(
+                options.normalize_word("ΣΟΦΙΑ", Some(Language::Greek)),
+                "σοφια"
+            );
+            assert_eq!(
+                options.normalize_word("ςοφια", Some(Language::Greek)),
+                "σοφια"
+            );
+            assert_eq!(
+                options.normalize_word("ISPARTA", Some(Language::Turkish)),
+                "ısparta"
+            );
+            assert_eq!(
+                options.normalize_word("İSTANBUL", Some(Language::Turkish)),
+                "istanbul"
+            );
+        }
+
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::German)),
+            "Straße STRASSE strasse",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["strasse"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_numbers() {
+        let lex = |text, locale| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(locale)),
+                text,
+                TokenLexerOptions {
+                    normalize_numbers: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lex("Revenue of 1,000,000 at 3.14 percent", Language::English),
+            vec!["revenue", "1000000", "3.14", "percent"]
+        );
+        assert_eq!(
+            lex(
+                "Umsatz 1.000.000, Marge 3,14, Kunden 2.500",
+                Language::German
+            ),
+            vec!["umsatz", "1000000", "marge", "3.14", "kunden", "2500"]
+        );
+        assert_eq!(
+            lex("Version 1.2.3 and 1,5", Language::English),
+            vec!["version", "1.2.3", "1,5"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_emoji_modifiers() {
+        let words = ["👍", "👍🏽", "👍\u{FE0F}", "👩🏽\u{200D}💻"];
+
+        let options = TokenLexerOptions::default();
+
+        assert_eq!(
+            words
+                .iter()
+                .map(|word| options.normalize_word(word, None))
+                .collect::<Vec<_>>(),
+            vec!["👍", "👍🏽", "👍\u{FE0F}", "👩🏽\u{200D}💻"]
+        );
+
+        let options = TokenLexerOptions {
+            strip_emoji_modifiers: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            words
+                .iter()
+                .map(|word| options.normalize_word(word, None))
+                .collect::<Vec<_>>(),
+            vec!["👍", "👍", "👍", "👩\u{200D}💻"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_joiners() {
+        let options = || TokenLexerOptions {
+            strip_joiners: true,
+            ..Default::default()
+        };
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "Co\u{200D}operate क्\u{200D}ष",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["cooperate", "क्\u{200D}ष"]
+        );
+
+        let tokens = ["👩\u{200D}💻"];
+        let token_cleaner =
+            TokenLexerBuilder::from_tokens(TokenLexerMode::NormalizeOnly, &tokens, options())
+                .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["👩\u{200D}💻"]
+        );
+
+        let token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "Co\u{200D}operate").unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["co\u{200D}operate"]
+        );
+    }
+
+    #[test]
+    fn it_maps_mode_from_query_lang() {
+        assert!(
+            TokenLexerMode::from_query_lang(Some(QueryGenericLang::Auto))
+                == TokenLexerMode::NormalizeAndCleanup(None)
+        );
+        assert!(
+            TokenLexerMode::from_query_lang(Some(QueryGenericLang::Disabled))
+                == TokenLexerMode::NormalizeOnly
+        );
+        assert!(
+            TokenLexerMode::from_query_lang(Some(QueryGenericLang::Enabled(Language::French)))
+                == TokenLexerMode::NormalizeAndCleanup(Some(Language::French))
+        );
+        assert!(TokenLexerMode::from_query_lang(None) == TokenLexerMode::NormalizeAndCleanup(None));
+    }
+
+    #[test]
+    fn it_cleans_token_lang_hinted() {
+        let mut token_cleaner_right = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "This will be cleaned properly, as English was hinted rightfully so.",
+        )
+        .unwrap();
+        let mut token_cleaner_wrong = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::French)),
+            "This will not be cleaned properly, as French was hinted but this is English.",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner_right.locale, Some(Language::English));
+        assert_eq!(token_cleaner_wrong.locale, Some(Language::French));
+
+        assert_eq!(
+            token_cleaner_right.next(),
+            Some(("cleaned".to_string(), 3550382624))
+        );
+        assert_eq!(
+            token_cleaner_wrong.next(),
+            Some(("this".to_string(), 493303710))
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_script_hinted_latin() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanupScript(Script::Latin),
+            "Le vif renard brun saute par dessus le chien paresseux.",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::French));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("renard".to_string(), 1635186311))
+        );
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_cleans_token_script_hinted_chinese_jieba() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanupScript(Script::Mandarin),
+            "我们中出了一个叛徒",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
+        assert_eq!(token_cleaner.next(), Some(("出".to_string(), 241978070)));
+        assert_eq!(token_cleaner.next(), Some(("一个".to_string(), 2596274530)));
+        assert_eq!(token_cleaner.next(), Some(("叛徒".to_string(), 3244183759)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_ukrainian_apostrophe() {
+        let mut token_cleaner_ascii = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Ukrainian)),
+            "ім'я",
+        )
+        .unwrap();
+        let mut token_cleaner_typographic = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Ukrainian)),
+            "Ім’я",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner_ascii.next(),
+            Some(("ім'я".to_string(), 1493158004))
+        );
+        assert_eq!(token_cleaner_ascii.next(), None);
+
+        assert_eq!(
+            token_cleaner_typographic.next(),
+            Some(("ім'я".to_string(), 1493158004))
+        );
+        assert_eq!(token_cleaner_typographic.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_punctuation_default() {
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "C++ or node.js").unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("c".to_string(), 4004515611)));
+        assert_eq!(token_cleaner.next(), Some(("or".to_string(), 3045685748)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("node.js".to_string(), 2059782963))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_punctuation_retained() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "C++, C# or .NET + node.js",
+            TokenLexerOptions {
+                retain_punctuation: vec!['+', '#', '.'],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("c++".to_string(), 1821962195)));
+        assert_eq!(token_cleaner.next(), Some(("c#".to_string(), 877430513)));
+        assert_eq!(token_cleaner.next(), Some(("or".to_string(), 3045685748)));
+        assert_eq!(token_cleaner.next(), Some((".net".to_string(), 4040308152)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("node.js".to_string(), 2059782963))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_too_long_default() {
+        let text = format!("fox {} dog", "z".repeat(TEXT_WORD_MAX_BYTES + 1));
+
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, &text).unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(token_cleaner.next(), Some(("dog".to_string(), 2044924251)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_too_long_configured() {
+        let decisions = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "fox zzzzzzzzzzzzzzzzzzzz",
+            TokenLexerOptions {
+                max_word_bytes: 16,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .explain();
+
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].outcome, TokenDecisionOutcome::Yielded);
+        assert_eq!(decisions[1].normalized, None);
+        assert_eq!(decisions[1].hash, None);
+        assert_eq!(decisions[1].outcome, TokenDecisionOutcome::DroppedJunk);
+    }
+
+    #[test]
+    fn it_cleans_token_windowed() {
+        let mut token_cleaner_whole = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "The quick brown fox jumps",
+            TokenLexerOptions {
+                max_chars: Some(15),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut token_cleaner_cut = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "The quick brown fox jumps",
+            TokenLexerOptions {
+                max_chars: Some(13),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner_whole.next(),
+            Some(("the".to_string(), 1098390085))
+        );
+        assert_eq!(
+            token_cleaner_whole.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(
+            token_cleaner_whole.next(),
+            Some(("brown".to_string(), 1268820067))
+        );
+        assert_eq!(token_cleaner_whole.next(), None);
+
+        assert_eq!(
+            token_cleaner_cut.next(),
+            Some(("the".to_string(), 1098390085))
+        );
+        assert_eq!(
+            token_cleaner_cut.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(token_cleaner_cut.next(), None);
+    }
+
+    #[test]
+    fn it_windows_text_grapheme_safe() {
+        assert_eq!(TokenLexerBuilder::window_text("fox dog", 100), "fox dog");
+        assert_eq!(TokenLexerBuilder::window_text("fox dog", 4), "fox ");
+        assert_eq!(TokenLexerBuilder::window_text("cafe\u{301} noir", 4), "");
+        assert_eq!(
+            TokenLexerBuilder::window_text("cafe\u{301} noir", 6),
+            "cafe\u{301} "
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_from_bytes() {
+        let mut token_cleaner = TokenLexerBuilder::from_bytes(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The quick fox".as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_fails_token_from_bytes_invalid_utf8() {
+        let error =
+            TokenLexerBuilder::from_bytes(TokenLexerMode::NormalizeOnly, b"quick \xC3\x28 fox")
+                .err();
+
+        assert_eq!(error, Some(TokenLexerError::InvalidUtf8(6)));
+        assert_eq!(error.unwrap().to_string(), "invalid_utf8(at byte 6)");
+
+        assert_eq!(
+            TokenLexerBuilder::from_bytes(TokenLexerMode::NormalizeOnly, b"\xFF").err(),
+            Some(TokenLexerError::InvalidUtf8(0))
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_stopword_phrases() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Fast track from a fast farmer who will track deer on the fast track",
+            TokenLexerOptions {
+                stopword_phrases: vec!["fast track".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["fast", "farmer", "track", "deer"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_preserved_phrases() {
+        let options = || TokenLexerOptions {
+            preserve_phrases: vec!["The Who".to_string(), "a tribe called quest".to_string()],
+            ..Default::default()
+        };
+        let mode = || TokenLexerMode::NormalizeAndCleanup(Some(Language::English));
+
+        let mut token_cleaner =
+            TokenLexerBuilder::from_with_options(mode(), "the who", options()).unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("the".to_string(), 1098390085)));
+        assert_eq!(token_cleaner.next(), Some(("who".to_string(), 1040371494)));
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner =
+            TokenLexerBuilder::from_with_options(mode(), "the quick", options()).unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            mode(),
+            "Listen to a Tribe Called Quest, or a tribe",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("listen".to_string(), 1648130384))
+        );
+        assert_eq!(token_cleaner.next(), Some(("a".to_string(), 1426945110)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("tribe".to_string(), 3648088524))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("called".to_string(), 2225158684))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quest".to_string(), 2755543280))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(not(feature = "tokenizer-whitespace"))]
+    #[test]
+    fn it_cleans_token_offsets_astral() {
+        let text = "𠮷野家";
+
+        let offsets = TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, text)
+            .unwrap()
+            .offsets()
+            .collect::<Vec<_>>();
+
+        assert_eq!(offsets[0].0, "𠮷");
+        assert_eq!(offsets[0].2, 0..4);
+
+        for (word, _, range) in offsets {
+            assert_eq!(&text[range], word);
+        }
+    }
+
+    #[test]
+    fn it_cleans_token_offsets() {
+        let text = "The quick brown fox";
+
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+        )
+        .unwrap()
+        .offsets();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), StoreTermHash::from("quick"), 4..9))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("brown".to_string(), StoreTermHash::from("brown"), 10..15))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("fox".to_string(), StoreTermHash::from("fox"), 16..19))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_offsets_max_yields() {
+        let text = "The quick brown fox jumps over the lazy dog";
+
+        let lexer = || {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                text,
+                TokenLexerOptions {
+                    max_yields: Some(3),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let offsets = lexer().offsets().collect::<Vec<_>>();
+
+        assert_eq!(
+            offsets
+                .iter()
+                .map(|(word, term_hash, _)| (word.to_string(), *term_hash))
+                .collect::<Vec<_>>(),
+            lexer().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            offsets
+                .iter()
+                .map(|(_, _, range)| &text[range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["quick", "brown", "fox"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_arena() {
+        let text = "The quick brown fox jumps over the lazy dog! The QUICK fox.";
+        let mode = || TokenLexerMode::NormalizeAndCleanup(Some(Language::English));
+
+        let arena = TokenLexerBuilder::from(mode(), text)
+            .unwrap()
+            .collect_arena();
+
+        assert_eq!(arena.len(), 6);
+        assert_eq!(
+            arena
+                .iter()
+                .map(|(word, term_hash)| (word.to_string(), term_hash))
+                .collect::<Vec<_>>(),
+            TokenLexerBuilder::from(mode(), text)
+                .unwrap()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_diffs_token_previous() {
+        let mode = || TokenLexerMode::NormalizeAndCleanup(Some(Language::English));
+
+        let previous = TokenLexerBuilder::from(mode(), "The quick brown fox")
+            .unwrap()
+            .hashes_only()
+            .collect::<HashSet<StoreTermHashed>>();
+
+        let (added, removed) = TokenLexerBuilder::from(mode(), "The quick red fox")
+            .unwrap()
+            .diff(&previous);
+
+        assert_eq!(
+            added.into_iter().collect::<Vec<_>>(),
+            vec![StoreTermHash::from("red")]
+        );
+        assert_eq!(
+            removed.into_iter().collect::<Vec<_>>(),
+            vec![StoreTermHash::from("brown")]
+        );
+
+        let (added, removed) = TokenLexerBuilder::from(mode(), "The quick brown fox")
+            .unwrap()
+            .diff(&previous);
+
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn it_diffs_token_previous_companions() {
+        let lexer = |text| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                text,
+                TokenLexerOptions {
+                    shingle_sizes: Some(2..=2),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let previous = lexer("quick brown fox")
+            .map(|value| value.1)
+            .collect::<HashSet<StoreTermHashed>>();
+
+        let (added, removed) = lexer("quick red fox").diff(&previous);
+
+        let mut added = added.into_iter().collect::<Vec<_>>();
+        let mut removed = removed.into_iter().collect::<Vec<_>>();
+
+        added.sort_unstable();
+        removed.sort_unstable();
+
+        let mut added_expected = vec![
+            StoreTermHash::from("red"),
+            StoreTermHash::from("quick red"),
+            StoreTermHash::from("red fox"),
+        ];
+        let mut removed_expected = vec![
+            StoreTermHash::from("brown"),
+            StoreTermHash::from("quick brown"),
+            StoreTermHash::from("brown fox"),
+        ];
+
+        added_expected.sort_unstable();
+        removed_expected.sort_unstable();
+
+        assert_eq!(added, added_expected);
+        assert_eq!(removed, removed_expected);
+    }
+
+    #[test]
+    fn it_cleans_token_single_word() {
+        let mode = || TokenLexerMode::NormalizeAndCleanup(Some(Language::English));
+
+        let single_word = TokenLexerBuilder::from(mode(), "Sonic")
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(single_word, vec![("sonic".to_string(), 956991807)]);
+        assert_eq!(
+            TokenLexerBuilder::from(mode(), " Sonic")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            single_word
+        );
+
+        assert_eq!(TokenLexerBuilder::from(mode(), "the").unwrap().next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_max_repeats() {
+        let options = |max_repeats| TokenLexerOptions {
+            max_repeats,
+            ..Default::default()
+        };
+        let text = "Fox fox, the FOX!";
+
+        let token_cleaner =
+            TokenLexerBuilder::from_with_options(TokenLexerMode::NormalizeOnly, text, options(2))
+                .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["fox", "fox", "the"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            text,
+            options(TOKEN_REPEATS_UNLIMITED),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["fox", "fox", "the", "fox"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_shared_yields() {
+        let mut yields = HashSet::new();
+
+        let first_tokens: Vec<(String, StoreTermHashed)> =
+            TokenLexerBuilder::from_with_shared_yields(
+                TokenLexerMode::NormalizeOnly,
+                "quick fox",
+                TokenLexerOptions::default(),
+                &mut yields,
+            )
+            .unwrap()
+            .collect();
+
+        let second_tokens: Vec<(String, StoreTermHashed)> =
+            TokenLexerBuilder::from_with_shared_yields(
+                TokenLexerMode::NormalizeOnly,
+                "lazy fox",
+                TokenLexerOptions::default(),
+                &mut yields,
+            )
+            .unwrap()
+            .collect();
+
+        assert_eq!(
+            first_tokens,
+            vec![
+                ("quick".to_string(), 4179131656),
+                ("fox".to_string(), 667256324)
+            ]
+        );
+        assert_eq!(second_tokens, vec![("lazy".to_string(), 4130433347)]);
+        assert_eq!(yields.len(), 3);
+    }
+
+    #[test]
+    fn it_explains_token_decisions() {
+        let decisions = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The quick Quick",
+        )
+        .unwrap()
+        .explain();
+
+        assert_eq!(decisions.len(), 3);
+
+        assert_eq!(decisions[0].word, "The");
+        assert_eq!(decisions[0].normalized.as_deref(), Some("the"));
+        assert_eq!(decisions[0].outcome, TokenDecisionOutcome::DroppedStopword);
+
+        assert_eq!(decisions[1].word, "quick");
+        assert_eq!(decisions[1].hash, Some(4179131656));
+        assert_eq!(decisions[1].outcome, TokenDecisionOutcome::Yielded);
+
+        assert_eq!(decisions[2].word, "Quick");
+        assert_eq!(decisions[2].normalized.as_deref(), Some("quick"));
+        assert_eq!(decisions[2].hash, Some(4179131656));
+        assert_eq!(decisions[2].outcome, TokenDecisionOutcome::DroppedDuplicate);
+    }
+
+    #[test]
+    fn it_cleans_token_norwegian_bokmal() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Bokmal)),
+            "Jeg har huset og boka",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("huset".to_string(), 3049168515))
+        );
+        assert_eq!(token_cleaner.next(), Some(("boka".to_string(), 2365435326)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_modifiers_only() {
+        let text = "ʰ\u{0301}ˠ café";
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            text,
+            TokenLexerOptions {
+                drop_modifiers_only: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["café"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, text).unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["ʰ\u{0301}ˠ", "café"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_split_digits() {
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "abc123def").unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("abc123def".to_string(), 3106164834))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "ABC123def 4x4",
+            TokenLexerOptions {
+                split_digits: TokenLexerDigitsSplit::Parts,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("abc".to_string(), 852579327)));
+        assert_eq!(token_cleaner.next(), Some(("123".to_string(), 3062191159)));
+        assert_eq!(token_cleaner.next(), Some(("def".to_string(), 2329727990)));
+        assert_eq!(token_cleaner.next(), Some(("4".to_string(), 1842982710)));
+        assert_eq!(token_cleaner.next(), Some(("x".to_string(), 784609514)));
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "abc123def",
+            TokenLexerOptions {
+                split_digits: TokenLexerDigitsSplit::PartsAndWhole,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("abc123def".to_string(), 3106164834))
+        );
+        assert_eq!(token_cleaner.next(), Some(("abc".to_string(), 852579327)));
+        assert_eq!(token_cleaner.next(), Some(("123".to_string(), 3062191159)));
+        assert_eq!(token_cleaner.next(), Some(("def".to_string(), 2329727990)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_token_lang_densest_window() {
+        let text = concat!(
+            "SKU-4471 rev. B4 / RoHS / IPX7 / UL94-V0 / PCB FR4 1.6mm / MCU STM32F405RGT6 / ",
+            "BLE 5.0 / USB-C PD 3.0 / LiPo 3.7V 2000mAh / IEC 62368-1 / EAN 3760123456789 / ",
+            "HS 8517.62 / FCC ID 2AXYZ-4471 / ",
+            "Le boîtier est conçu pour une utilisation en extérieur, et la batterie tient une ",
+            "semaine entière par le froid."
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            text,
+            TokenLexerOptions {
+                detect_densest_words: Some(12),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::French));
+    }
+
+    #[test]
+    fn it_cleans_token_from_tokens() {
+        let tokens = ["Hello", "WORLD", "hello"];
+
+        let mut token_cleaner = TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            &tokens,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("hello".to_string(), 4211111929))
+        );
+        assert_eq!(token_cleaner.next(), Some(("world".to_string(), 413819571)));
+        assert_eq!(token_cleaner.next(), None);
+
+        let tokens = ["The", "quick brown", "fox"];
+
+        let mut token_cleaner = TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            &tokens,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick brown".to_string(), 1986822131))
+        );
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(token_cleaner.next(), None);
+
+        let tokens = ["SKU-1042", "Quick", "sku-77", "fox"];
+
+        let token_cleaner = TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeOnly,
+            &tokens,
+            TokenLexerOptions {
+                allow_filter: Some(Arc::new(|word: &str| word.starts_with("sku-"))),
+                max_yields: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["sku-1042"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_clitics() {
+        let options = || TokenLexerOptions {
+            split_clitics: true,
+            ..Default::default()
+        };
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Italian)),
+            "dell'acqua l’albero",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("acqua".to_string(), 3708239076))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("albero".to_string(), 582388084))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Catalan)),
+            "L'home porta'ls",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("home".to_string(), 3801215962)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("porta".to_string(), 1879219763))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "l'home",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("l'home".to_string(), 2424837299))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        // Clitics are kept by default, as splitting them changes already-indexed words
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Italian)),
+            "dell'acqua",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["dell'acqua"]
+        );
+    }
+
+    #[test]
+    fn it_counts_token_decisions() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "the quick the brown quick",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.by_ref().count(), 2);
+
+        assert_eq!(
+            token_cleaner.counts(),
+            &TokenLexerCounts {
+                seen: 5,
+                unique: 2,
+                stopwords: 2,
+                duplicates: 1,
+                junk: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_soft_hyphens() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeOnly,
+            "Hyphen\u{00AD}ation of para\u{2060}graphs",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("hyphenation".to_string(), 4203164675))
+        );
+        assert_eq!(token_cleaner.next(), Some(("of".to_string(), 1246317676)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("paragraphs".to_string(), 3259306741))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeOnly,
+            "Hyphen\u{00AD}ation of para\u{2060}graphs",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner
+                .hashes_only()
+                .collect::<Vec<StoreTermHashed>>(),
+            vec![
+                StoreTermHash::from("hyphenation"),
+                StoreTermHash::from("of"),
+                StoreTermHash::from("paragraphs")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_shingles() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "quick brown fox quick brown",
+            TokenLexerOptions {
+                shingle_sizes: Some(2..=2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick".to_string(), 4179131656))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("brown".to_string(), 1268820067))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("quick brown".to_string(), 1986822131))
+        );
+        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("brown fox".to_string(), 682458133))
+        );
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("fox quick".to_string(), 3099471291))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        let text = "the quick and the brown fox";
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+            TokenLexerOptions {
+                shingle_sizes: Some(2..=3),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![
+                "quick",
+                "brown",
+                "quick brown",
+                "fox",
+                "brown fox",
+                "quick brown fox"
+            ]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            text,
+            TokenLexerOptions {
+                shingle_sizes: Some(2..=3),
+                shingle_skip_stopwords: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["quick", "brown", "fox", "brown fox"]
+        );
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_token_lang_mixed_script() {
+        let text = "我们今天很高兴见到你 the quick brown fox is here";
+
+        let lexer_locale = |policy| {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                text,
+                TokenLexerOptions {
+                    mixed_script_policy: Some(policy),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .locale
+        };
+
+        assert_eq!(
+            lexer_locale(TokenLexerMixedScriptPolicy::PreferLongestRun),
+            Some(Language::English)
+        );
+        assert_eq!(
+            lexer_locale(TokenLexerMixedScriptPolicy::PreferFirstRun),
+            Some(Language::Chinese)
+        );
+        assert_eq!(
+            lexer_locale(TokenLexerMixedScriptPolicy::PreferConfiguredOrder(vec![
+                Script::Cyrillic,
+                Script::Latin
+            ])),
+            Some(Language::English)
+        );
+        assert_eq!(
+            lexer_locale(TokenLexerMixedScriptPolicy::PreferConfiguredOrder(vec![
+                Script::Cyrillic
+            ])),
+            Some(Language::Chinese)
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_identifier() {
+        let text = "550E8400-e29b-41d4-a716-446655440000";
+        let before = LexerMetrics::thread_snapshot();
+
+        let options = || TokenLexerOptions {
+            identifier_patterns: vec![
+                TokenLexerIdentifierPattern::Uuid,
+                TokenLexerIdentifierPattern::Hex(16),
+                TokenLexerIdentifierPattern::Base64(24),
+            ],
+            ..Default::default()
+        };
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            text,
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(
+            token_cleaner.next(),
+            Some((
+                "550e8400-e29b-41d4-a716-446655440000".to_string(),
+                1290628481
+            ))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        assert_eq!(
+            LexerMetrics::thread_snapshot().detection_calls,
+            before.detection_calls
+        );
+
+        // Notice: the whitespace tokenizer does not split words on hyphens.
+        #[cfg(not(feature = "tokenizer-whitespace"))]
+        {
+            let token_cleaner =
+                TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), text).unwrap();
+
+            assert_eq!(token_cleaner.count(), 5);
+        }
+
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            " d41d8cd98f00b204e9800998ecf8427e ",
+            options(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("d41d8cd98f00b204e9800998ecf8427e".to_string(), 2668463923))
+        );
+        assert_eq!(token_cleaner.next(), None);
+
+        assert!(TokenLexerIdentifierPattern::Base64(24).matches("dGhlIHF1aWNrIGJyb3duIGZveA=="));
+        assert!(!TokenLexerIdentifierPattern::Base64(24).matches("Antidisestablishmentarianism"));
+    }
+
+    #[test]
+    fn it_cleans_token_connectors() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "user_id user‿name",
+            TokenLexerOptions {
+                join_connectors: TokenLexerConnectors::Underscore,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["user_id", "user‿name"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "user_id user‿name",
+            TokenLexerOptions {
+                join_connectors: TokenLexerConnectors::Punctuation,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["user_id", "user‿name"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_uppercase() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The quick fox",
+            TokenLexerOptions {
+                case: TokenLexerCase::Upper,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("QUICK".to_string(), 3156106758))
+        );
+        assert_eq!(token_cleaner.next(), Some(("FOX".to_string(), 1967628839)));
+        assert_eq!(token_cleaner.next(), None);
+
+        assert_ne!(StoreTermHash::from("FOX"), StoreTermHash::from("fox"));
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "Fox fox",
+            TokenLexerOptions {
+                case: TokenLexerCase::Upper,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner
+                .hashes_only()
+                .collect::<Vec<StoreTermHashed>>(),
+            vec![StoreTermHash::from("FOX")]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_collapsed_repeats() {
+        let text = "LOOOL loool looooool lool cool 1000";
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            text,
+            TokenLexerOptions {
+                collapse_repeats: Some(2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["lool", "cool", "1000"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "aaaaaaaa aaa",
+            TokenLexerOptions {
+                collapse_repeats: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["a"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_paths() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "/var/log/app.2019.log C:\\Users\\report.pdf",
+            TokenLexerOptions {
+                split_paths: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![
+                "var",
+                "log",
+                "app.2019.log",
+                "app.2019",
+                "c:",
+                "users",
+                "report.pdf",
+                "report",
+                "pdf"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_min_word_chars() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "猫",
+            TokenLexerOptions {
+                min_word_chars: TokenLexerMinWordChars::LanguageDefaults,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["猫"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "vitamin é supplement",
+            TokenLexerOptions {
+                min_word_chars: TokenLexerMinWordChars::LanguageDefaults,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["vitamin", "supplement"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "vitamin é supplement",
+            TokenLexerOptions {
+                min_word_chars: TokenLexerMinWordChars::LanguageOverrides(vec![(
+                    Language::English,
+                    1,
+                )]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["vitamin", "é", "supplement"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_social_tags() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "Loving #RustLang, ping @user_name or #日本 via me@example.com # @",
+            TokenLexerOptions {
+                social_tags: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![
+                "loving",
+                "#rustlang",
+                "ping",
+                "@user_name",
+                "or",
+                "#日本",
+                "via",
+                "me",
+                "example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_proper_nouns() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "I visited Paris and London. Then Paris",
+            TokenLexerOptions {
+                mark_proper_nouns: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![
+                "i",
+                "visited",
+                "paris",
+                "paris__proper",
+                "and",
+                "london",
+                "london__proper",
+                "then"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_hashes_word() {
+        assert_eq!(TokenLexerBuilder::hash_word("quick"), 4179131656);
+        assert_eq!(TokenLexerBuilder::hash_word("fox"), 667256324);
+        assert_eq!(TokenLexerBuilder::hash_word("sonic"), 956991807);
+        assert_eq!(TokenLexerBuilder::hash_word("北京"), 4211439552);
+
+        // Hashes must match those of the words yielded by the lexer
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The QUICK fox",
+        )
+        .unwrap();
+
+        for (word, term_hash) in token_cleaner {
+            assert_eq!(TokenLexerBuilder::hash_word(&word), term_hash);
+        }
+    }
+
+    #[test]
+    fn it_cleans_token_from_chunks() {
+        let token_cleaner = TokenLexerBuilder::from_chunks(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            vec!["The quick bro", "wn fox ", "jumps over the quick dog"].into_iter(),
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["quick", "brown", "fox", "jumps", "dog"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_chunks(
+            TokenLexerMode::NormalizeOnly,
+            vec!["Sonic", "Search", " backend"].into_iter(),
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["sonicsearch", "backend"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_from_chunks_state() {
+        let lex = |chunks: Vec<&'static str>, options| {
+            TokenLexerBuilder::from_chunks(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                chunks.into_iter(),
+                options,
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>()
+        };
+
+        let shingles = || TokenLexerOptions {
+            shingle_sizes: Some(2..=2),
+            ..Default::default()
+        };
+
+        // Shingles span chunk edges, whether a word straddles the edge or not
+        assert_eq!(
+            lex(vec!["quick bro", "wn fox"], shingles()),
+            vec!["quick", "brown", "quick brown", "fox", "brown fox"]
+        );
+        assert_eq!(
+            lex(vec!["quick ", "brown fox"], shingles()),
+            lex(vec!["quick brown fox"], shingles())
+        );
+
+        // The maximum number of yielded words applies to all chunks
+        assert_eq!(
+            lex(
+                vec!["quick brown ", "fox jumps ", "dog"],
+                TokenLexerOptions {
+                    max_yields: Some(3),
+                    ..Default::default()
+                }
+            ),
+            vec!["quick", "brown", "fox"]
+        );
+
+        // Stopwords are not kept on parts of a text, even if parts are short
+        assert_eq!(
+            lex(
+                vec!["The quick brown fox jumps over ", "the lazy dog"],
+                TokenLexerOptions {
+                    keep_stopwords_under_words: Some(4),
+                    ..Default::default()
+                }
+            ),
+            vec!["quick", "brown", "fox", "jumps", "lazy", "dog"]
+        );
+
+        // The undetermined marker applies to all chunks, and is yielded once
+        assert_eq!(
+            TokenLexerBuilder::from_chunks(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                vec!["🚀 🙋‍♂️", "🙋‍♂️ 🚀"].into_iter(),
+                TokenLexerOptions {
+                    mark_undetermined: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .map(|value| value.0)
+            .collect::<Vec<_>>(),
+            vec![TOKEN_UNDETERMINED_MARKER]
+        );
+
+        // Chunks that hold no whitespace are not carried over past the longest word
+        let mut token_cleaner = TokenLexerBuilder::from_chunks(
+            TokenLexerMode::NormalizeOnly,
+            vec!["abc", "def", "ghi"].into_iter(),
+            TokenLexerOptions {
+                max_word_bytes: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("ghi".to_string(), StoreTermHash::from("ghi")))
+        );
+        assert!(token_cleaner.carry.is_empty());
+    }
+
+    #[test]
+    fn it_cleans_token_ligatures() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "\u{FB01}le file \u{01C4}ep \u{01C6}ep ²",
+            TokenLexerOptions {
+                fold_ligatures: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["file", "d\u{017E}ep", "²"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_segmentation() {
+        #[cfg(not(feature = "tokenizer-whitespace"))]
+        {
+            let token_cleaner =
+                TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "foo.bar-baz").unwrap();
+
+            assert_eq!(
+                token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+                vec!["foo.bar", "baz"]
+            );
+        }
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "foo.bar-baz cafe\u{0301}",
+            TokenLexerOptions {
+                segmentation: TokenLexerSegmentation::AlphanumericRuns,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["foo", "bar", "baz", "cafe\u{0301}"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_punctuation_only() {
+        let tokens = ["\u{2014}", "'", "fox", "..."];
+
+        let token_cleaner = TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeOnly,
+            &tokens,
+            TokenLexerOptions {
+                drop_punctuation_only: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["fox"]
+        );
+
+        let token_cleaner = TokenLexerBuilder::from_tokens(
+            TokenLexerMode::NormalizeOnly,
+            &tokens,
+            TokenLexerOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["\u{2014}", "'", "fox", "..."]
+        );
+
+        // Path components are not segmented, thus may be made of punctuation only
+        for (drop_punctuation_only, expected) in [
+            (true, vec!["var", "log"]),
+            (false, vec!["var", "\u{2014}", "log"]),
+        ] {
+            let token_cleaner = TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeOnly,
+                "/var/\u{2014}/log",
+                TokenLexerOptions {
+                    split_paths: true,
+                    drop_punctuation_only,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn it_estimates_token_footprint() {
+        let estimate = |text| {
+            TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                text,
+            )
+            .unwrap()
+            .estimate_footprint()
+        };
+
+        let footprint = estimate("The quick brown fox jumps over the lazy dog!");
+
+        assert_eq!(
+            footprint,
+            TokenLexerFootprint {
+                terms: 6,
+                term_bytes: 25,
+                hash_bytes: 24,
+            }
+        );
+        assert_eq!(footprint.total_bytes(), 49);
+
+        // Repeated words do not add to the footprint, while unique words do
+        assert_eq!(
+            estimate("The quick brown fox jumps over the lazy dog! The quick brown fox!"),
+            footprint
+        );
+        assert_eq!(
+            estimate("The quick brown fox jumps over the lazy dog! Swift yellow cat runs past a sleepy bird!")
+                .hash_bytes,
+            footprint.hash_bytes * 2
+        );
+    }
+
+    #[test]
+    fn it_collects_token_with_stopwords() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "What is the answer to the question?",
+        )
+        .unwrap();
+
+        let (words, stopwords) = token_cleaner.collect_with_stopwords();
+
+        assert_eq!(
+            words.into_iter().map(|value| value.0).collect::<Vec<_>>(),
+            vec!["answer", "question"]
+        );
+        assert_eq!(stopwords, vec!["what", "is", "the", "to", "the"]);
+    }
+
+    #[test]
+    fn it_cleans_token_allow_filter() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Ship ABC-1234 and XYZ-9876 today, but not AB-12 nor ABCD-123",
+            TokenLexerOptions {
+                retain_punctuation: vec!['-'],
+                allow_filter: Some(Arc::new(|word: &str| {
+                    let bytes = word.as_bytes();
+
+                    bytes.len() == 8
+                        && bytes[..3].iter().all(u8::is_ascii_lowercase)
+                        && bytes[3] == b'-'
+                        && bytes[4..].iter().all(u8::is_ascii_digit)
+                })),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["abc-1234", "xyz-9876"]
+        );
+    }
+
+    #[test]
+    fn it_interns_token_ids() {
+        let mut interner = TokenLexerInterner::new();
+
+        let first = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Quick brown foxes jump",
+        )
+        .unwrap()
+        .interned(&mut interner)
+        .collect::<Vec<_>>();
+
+        let second = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Lazy brown dogs jump",
+        )
+        .unwrap()
+        .interned(&mut interner)
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            first
+                .iter()
+                .map(|(word, _, id)| (word.as_str(), *id))
+                .collect::<Vec<_>>(),
+            vec![("quick", 0), ("brown", 1), ("foxes", 2), ("jump", 3)]
+        );
+        assert_eq!(
+            second
+                .iter()
+                .map(|(word, _, id)| (word.as_str(), *id))
+                .collect::<Vec<_>>(),
+            vec![("lazy", 4), ("brown", 1), ("dogs", 5), ("jump", 3)]
+        );
+        assert_eq!(second[1].1, StoreTermHash::from("brown"));
+
+        assert_eq!(interner.len(), 6);
+        assert_eq!(interner.id("dogs"), Some(5));
+        assert_eq!(interner.id("cats"), None);
+    }
+
+    #[test]
+    fn it_collects_token_into_buffer() {
+        let mut buffer = Vec::new();
+
+        TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Quick brown foxes jump over lazy dogs",
+        )
+        .unwrap()
+        .collect_into(&mut buffer);
+
+        assert_eq!(
+            buffer
+                .iter()
+                .map(|(word, _)| word.as_str())
+                .collect::<Vec<_>>(),
+            vec!["quick", "brown", "foxes", "jump", "lazy", "dogs"]
+        );
+
+        let capacity = buffer.capacity();
+
+        TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Sleepy cats",
+        )
+        .unwrap()
+        .collect_into(&mut buffer);
+
+        assert_eq!(
+            buffer,
+            vec![
+                ("sleepy".to_string(), StoreTermHash::from("sleepy")),
+                ("cats".to_string(), StoreTermHash::from("cats"))
+            ]
+        );
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn it_collects_token_by_frequency() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "Sonic is fast. Sonic indexes documents, and Sonic searches documents. Sonic rocks!",
+        )
+        .unwrap();
+
+        let words = token_cleaner.collect_by_frequency();
+
+        assert_eq!(words[0].0, "sonic");
+        assert_eq!(words[0].1, StoreTermHash::from("sonic"));
+        assert_eq!(words[0].2, 4);
+        assert_eq!(
+            words
+                .iter()
+                .map(|(word, _, count)| (word.as_str(), *count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("sonic", 4),
+                ("documents", 2),
+                ("fast", 1),
+                ("indexes", 1),
+                ("searches", 1),
+                ("rocks", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_collects_token_by_frequency_repeats() {
+        for max_repeats in [2, TOKEN_REPEATS_UNLIMITED].iter() {
+            let token_cleaner = TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                "Sonic is fast. Sonic indexes documents, and Sonic searches documents. Sonic!",
+                TokenLexerOptions {
+                    max_repeats: *max_repeats,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                token_cleaner
+                    .collect_by_frequency()
+                    .iter()
+                    .map(|(word, _, count)| (word.as_str(), *count))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("sonic", 4),
+                    ("documents", 2),
+                    ("fast", 1),
+                    ("indexes", 1),
+                    ("searches", 1)
+                ]
+            );
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn it_yields_token_first_occurrence_order() {
+        let words = [
+            "fox", "dog", "the", "jumps", "lazy", "and", "brown", "quick", "over", "farmer",
+        ];
+
+        let mut seed: u32 = 42;
+
+        for _ in 0..200 {
+            // Generate a pseudo-random text made of words (with repeats), using a xorshift
+            let mut text_words = Vec::new();
+
+            for _ in 0..(1 + seed % 24) {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+
+                text_words.push(words[seed as usize % words.len()]);
+            }
+
+            let text = text_words.join(" ");
+
+            let mut expected = Vec::new();
+
+            for word in &text_words {
+                if !LexerStopWord::is(word, Some(Language::English)) && !expected.contains(word) {
+                    expected.push(*word);
+                }
+            }
+
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                &text,
+            )
+            .unwrap();
+
+            assert_eq!(
+                token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+                expected,
+                "order of words in text: {}",
+                text
+            );
+        }
+    }
+
+    #[cfg(feature = "tokenizer-chinese")]
+    #[test]
+    fn it_yields_token_first_occurrence_order_chinese() {
+        let words = ["叛徒", "北京", "学生", "银行", "人民"];
+
+        let mut seed: u32 = 42;
+
+        for _ in 0..200 {
+            let mut text_words = Vec::new();
+
+            for _ in 0..(1 + seed % 12) {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+
+                text_words.push(words[seed as usize % words.len()]);
+            }
+
+            // Notice: words are separated with punctuation, as to get a stable segmentation
+            let text = text_words.join("，");
+
+            let mut expected = Vec::new();
+
+            for word in &text_words {
+                if !expected.contains(word) {
+                    expected.push(*word);
+                }
+            }
+
+            // Notice: the tokenizer yields punctuation as words, thus it is dropped.
+            let token_cleaner = TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+                &text,
+                TokenLexerOptions {
+                    drop_punctuation_only: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+                expected,
+                "order of words in text: {}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn it_cleans_token_expanded() {
+        let token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The dog chased another dog and a canine",
+        )
+        .unwrap()
+        .with_expansion(|word| match word {
+            "dog" => vec!["canine".to_string()],
+            "chased" => vec!["chase".to_string(), String::new()],
+            _ => Vec::new(),
+        });
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["dog", "canine", "chased", "chase"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_max_yields() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "Antidisestablishmentarianism forever",
+            TokenLexerOptions {
+                max_yields: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .with_expansion(|word| {
+            // Expand to all edge n-grams of the word (ie. its prefixes)
+            word.char_indices()
+                .skip(1)
+                .map(|(index, _)| word[..index].to_string())
+                .collect()
+        });
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec!["antidisestablishmentarianism", "a", "an", "ant", "anti"]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_reversed() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "Running kayak cafe extraordinarily",
+            TokenLexerOptions {
+                reverse_words: Some(8),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![
+                "running",
+                "gninnur",
+                "kayak",
+                "cafe",
+                "efac",
+                "extraordinarily"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_cleans_token_typography() {
+        let options = || TokenLexerOptions {
+            normalize_typography: true,
+            ..Default::default()
+        };
+        let mode = || TokenLexerMode::NormalizeAndCleanup(Some(Language::English));
+
+        let curly =
+            TokenLexerBuilder::from_with_options(mode(), "Rock’n’roll “lives” – on", options())
+                .unwrap()
+                .collect::<Vec<_>>();
+
+        assert_eq!(
+            curly,
+            vec![
+                ("rock'n'roll".to_string(), 2755561327),
+                ("lives".to_string(), 2839486784)
+            ]
+        );
+        assert_eq!(
+            TokenLexerBuilder::from_with_options(mode(), "Rock'n'roll \"lives\" - on", options())
+                .unwrap()
+                .collect::<Vec<_>>(),
+            curly
+        );
+
+        let mut token_cleaner = TokenLexerBuilder::from(mode(), "Rock’n’roll").unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("rock’n’roll".to_string(), 3062474341))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_catalan_middle_dot() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Catalan)),
+            "paral·lel paral\u{2027}lel PARAL.LEL",
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("paral·lel".to_string(), 2615760056))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_cleans_token_catalan_middle_dot_folded() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Catalan)),
+            "paral·lel parallel",
+            TokenLexerOptions {
+                fold_catalan_middle_dot: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            token_cleaner.next(),
+            Some(("parallel".to_string(), 1996133050))
+        );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    #[test]
+    fn it_falls_back_chinese_per_character() {
+        let mut token_cleaner = TokenLexerBuilder::from(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "北京华语",
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("北".to_string(), 2596145472)));
+        assert_eq!(token_cleaner.next(), Some(("京".to_string(), 3711742361)));
+        assert_eq!(token_cleaner.next(), Some(("华".to_string(), 441626995)));
+        assert_eq!(token_cleaner.next(), Some(("语".to_string(), 1007733849)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    #[test]
+    fn it_falls_back_chinese_bigram() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "北京华语 学 Sonic",
+            TokenLexerOptions {
+                chinese_fallback: TokenLexerChineseFallback::Bigram,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.next(), Some(("北京".to_string(), 4211439552)));
+        assert_eq!(token_cleaner.next(), Some(("京华".to_string(), 2498282189)));
+        assert_eq!(token_cleaner.next(), Some(("华语".to_string(), 3659023793)));
+        assert_eq!(token_cleaner.next(), Some(("学".to_string(), 2022139672)));
+        assert_eq!(token_cleaner.next(), Some(("sonic".to_string(), 956991807)));
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[cfg(not(feature = "tokenizer-chinese"))]
+    #[test]
+    fn it_falls_back_chinese_reject() {
+        let error = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::Chinese)),
+            "北京华语",
+            TokenLexerOptions {
+                chinese_fallback: TokenLexerChineseFallback::Reject,
+                ..Default::default()
+            },
+        )
+        .err();
+
+        assert_eq!(
+            error,
+            Some(TokenLexerError::UnsupportedLanguage(Language::Chinese))
+        );
+        assert_eq!(error.unwrap().to_string(), "unsupported_language(zho)");
+    }
+
+    #[test]
+    fn it_hashes_only_tokens_streamed() {
+        let text = "İSTANBUL Straße ÉCOLE école ΟΔΟΣ quick QUICK";
+
+        for case_mapping in [TokenLexerCaseMapping::Full, TokenLexerCaseMapping::Simple] {
+            let options = || TokenLexerOptions {
+                case_mapping,
+                ..Default::default()
+            };
+
+            let hashes = TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeOnly,
+                text,
+                options(),
+            )
+            .unwrap()
+            .map(|value| value.1)
+            .collect::<Vec<StoreTermHashed>>();
+
+            assert_eq!(hashes.len(), 5);
+            assert_eq!(
+                TokenLexerBuilder::from_with_options(
+                    TokenLexerMode::NormalizeOnly,
+                    text,
+                    options()
+                )
+                .unwrap()
+                .hashes_only()
+                .collect::<Vec<StoreTermHashed>>(),
+                hashes
+            );
+        }
+    }
+
+    #[test]
+    fn it_hashes_only_tokens_allow_filter() {
+        let text = "SKU-1042 Quick SKU-77 Brown ÉCOLE ——— ZZZ";
+
+        let options = || TokenLexerOptions {
+            retain_punctuation: vec!['-'],
+            allow_filter: Some(Arc::new(|word: &str| word.starts_with("sku-"))),
+            ..Default::default()
+        };
+
+        let hashes =
+            TokenLexerBuilder::from_with_options(TokenLexerMode::NormalizeOnly, text, options())
+                .unwrap()
+                .map(|value| value.1)
+                .collect::<Vec<StoreTermHashed>>();
+
+        assert_eq!(
+            hashes,
+            vec![
+                StoreTermHash::from("sku-1042"),
+                StoreTermHash::from("sku-77")
+            ]
+        );
+        assert_eq!(
+            TokenLexerBuilder::from_with_options(TokenLexerMode::NormalizeOnly, text, options())
+                .unwrap()
+                .hashes_only()
+                .collect::<Vec<StoreTermHashed>>(),
+            hashes
+        );
+    }
+
+    #[test]
+    fn it_hashes_only_tokens_companions() {
+        let text = "The Quick brown fox";
+
+        let options = || TokenLexerOptions {
+            shingle_sizes: Some(2..=2),
+            reverse_words: Some(8),
+            keep_original_case: true,
+            ..Default::default()
+        };
+
+        for mode in [
+            TokenLexerMode::NormalizeOnly,
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+        ] {
+            let hashes = TokenLexerBuilder::from_with_options(mode.clone(), text, options())
+                .unwrap()
+                .map(|value| value.1)
+                .collect::<Vec<StoreTermHashed>>();
+
+            assert!(hashes.contains(&StoreTermHash::from("kciuq")));
+            assert_eq!(
+                TokenLexerBuilder::from_with_options(mode, text, options())
+                    .unwrap()
+                    .hashes_only()
+                    .collect::<Vec<StoreTermHashed>>(),
+                hashes
+            );
+        }
+    }
+
+    #[test]
+    fn it_hashes_only_tokens_max_yields() {
+        let text = "Quick brown fox jumps over the lazy dog";
+
+        for mode in [
+            TokenLexerMode::NormalizeOnly,
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+        ] {
+            let options = || TokenLexerOptions {
+                max_yields: Some(3),
+                ..Default::default()
+            };
+
+            let hashes = TokenLexerBuilder::from_with_options(mode.clone(), text, options())
+                .unwrap()
+                .hashes_only()
+                .collect::<Vec<StoreTermHashed>>();
+
+            assert_eq!(hashes.len(), 3);
+            assert_eq!(
+                hashes,
+                TokenLexerBuilder::from_with_options(mode, text, options())
+                    .unwrap()
+                    .map(|value| value.1)
+                    .collect::<Vec<StoreTermHashed>>()
+            );
+        }
+    }
+
+    #[test]
+    fn it_hashes_only_tokens() {
+        let text = "The Quick brown fox, the quick ім’я PARAL·LEL!";
+
+        for locale in [Language::English, Language::Ukrainian, Language::Catalan] {
+            let mode = || TokenLexerMode::NormalizeAndCleanup(Some(locale));
+
+            let hashes = TokenLexerBuilder::from(mode(), text)
+                .unwrap()
+                .map(|value| value.1)
+                .collect::<Vec<StoreTermHashed>>();
+
+            assert!(!hashes.is_empty());
+            assert_eq!(
+                TokenLexerBuilder::from(mode(), text)
+                    .unwrap()
+                    .hashes_only()
+                    .collect::<Vec<StoreTermHashed>>(),
+                hashes
+            );
+        }
+    }
 
     #[test]
-    fn it_cleans_token_english() {
-        let mut token_cleaner = TokenLexerBuilder::from(
-            TokenLexerMode::NormalizeAndCleanup(None),
-            "The quick brown fox jumps over the lazy dog!",
-        )
-        .unwrap();
+    fn it_lowercases_token_full_case_mapping() {
+        let mut token_cleaner =
+            TokenLexerBuilder::from(TokenLexerMode::NormalizeOnly, "İstanbul").unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::English));
         assert_eq!(
             token_cleaner.next(),
-            Some(("quick".to_string(), 4179131656))
+            Some(("i\u{307}stanbul".to_string(), 518434279))
         );
+        assert_eq!(token_cleaner.next(), None);
+    }
+
+    #[test]
+    fn it_lowercases_token_simple_case_mapping() {
+        let mut token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeOnly,
+            "İstanbul",
+            TokenLexerOptions {
+                case_mapping: TokenLexerCaseMapping::Simple,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
         assert_eq!(
             token_cleaner.next(),
-            Some(("brown".to_string(), 1268820067))
+            Some(("istanbul".to_string(), 2770572787))
         );
-        assert_eq!(token_cleaner.next(), Some(("fox".to_string(), 667256324)));
-        assert_eq!(token_cleaner.next(), Some(("jumps".to_string(), 633865164)));
-        assert_eq!(token_cleaner.next(), Some(("lazy".to_string(), 4130433347)));
-        assert_eq!(token_cleaner.next(), Some(("dog".to_string(), 2044924251)));
         assert_eq!(token_cleaner.next(), None);
     }
 
     #[test]
-    fn it_cleans_token_french() {
+    fn it_normalizes_word_as_lexer() {
         let mut token_cleaner = TokenLexerBuilder::from(
-            TokenLexerMode::NormalizeAndCleanup(None),
-            "Le vif renard brun saute par dessus le chien paresseux.",
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+            "The QUICK fox",
         )
         .unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::French));
         assert_eq!(
-            token_cleaner.next(),
-            Some(("renard".to_string(), 1635186311))
+            token_cleaner.next().map(|(word, _)| word),
+            Some(normalize_word("QUICK", Some(Language::English)))
         );
-        assert_eq!(token_cleaner.next(), Some(("brun".to_string(), 2763604928)));
         assert_eq!(
-            token_cleaner.next(),
-            Some(("saute".to_string(), 1918158211))
+            token_cleaner.next().map(|(word, _)| word),
+            Some(normalize_word("fox", Some(Language::English)))
         );
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_lang_english_regular() {
         assert_eq!(
-            token_cleaner.next(),
-            Some(("chien".to_string(), 2177818351))
+            TokenLexerBuilder::detect_lang("The quick brown fox jumps over the lazy dog!", false),
+            Some(Language::English)
+        );
+    }
+
+    #[test]
+    fn it_truncates_detect_text() {
+        assert_eq!(
+            TokenLexerBuilder::truncate_detect_text("   The quick brown fox jumps", 1),
+            "The quick brown "
         );
         assert_eq!(
-            token_cleaner.next(),
-            Some(("paresseux".to_string(), 1678693110))
+            TokenLexerBuilder::truncate_detect_text(" 日本語", 0),
+            "日本語"
         );
-        assert_eq!(token_cleaner.next(), None);
+        assert_eq!(TokenLexerBuilder::truncate_detect_text("   ", 1), "");
     }
 
-    #[cfg(feature = "tokenizer-chinese")]
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_cleans_token_chinese_jieba() {
-        let mut token_cleaner = TokenLexerBuilder::from(
+    fn it_detects_token_lang_truncated() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
             TokenLexerMode::NormalizeAndCleanup(None),
-            "我们中出了一个叛徒",
+            "      The quick brown fox jumps over the lazy dog!",
+            TokenLexerOptions {
+                detect_max_chars: 1,
+                ..Default::default()
+            },
         )
         .unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
-        assert_eq!(token_cleaner.next(), Some(("出".to_string(), 241978070)));
-        assert_eq!(token_cleaner.next(), Some(("一个".to_string(), 2596274530)));
-        assert_eq!(token_cleaner.next(), Some(("叛徒".to_string(), 3244183759)));
-        assert_eq!(token_cleaner.next(), None);
+        assert_eq!(token_cleaner.locale, Some(Language::English));
     }
 
-    #[cfg(not(feature = "tokenizer-chinese"))]
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_cleans_token_chinese_naive() {
-        let mut token_cleaner = TokenLexerBuilder::from(
+    fn it_detects_token_lang_fields() {
+        let before = LexerMetrics::thread_snapshot();
+
+        let token_cleaners = TokenLexerBuilder::from_fields(
             TokenLexerMode::NormalizeAndCleanup(None),
-            "快狐跨懒狗快狐跨懒狗",
+            &[
+                "The quick brown fox",
+                "The quick brown fox jumps over the lazy dog, and then runs away from the farmer",
+                "A lazy dog",
+            ],
+            TokenLexerOptions::default(),
         )
         .unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::Chinese));
-        assert_eq!(token_cleaner.next(), Some(("快".to_string(), 126546256)));
-        assert_eq!(token_cleaner.next(), Some(("狐".to_string(), 2879689662)));
-        assert_eq!(token_cleaner.next(), Some(("跨".to_string(), 2913342670)));
-        assert_eq!(token_cleaner.next(), Some(("懒".to_string(), 3199935961)));
-        assert_eq!(token_cleaner.next(), Some(("狗".to_string(), 3360772096)));
-        assert_eq!(token_cleaner.next(), None);
+        assert_eq!(
+            LexerMetrics::thread_snapshot().detection_calls,
+            before.detection_calls + 1
+        );
+        assert_eq!(token_cleaners.len(), 3);
+
+        for token_cleaner in &token_cleaners {
+            assert_eq!(token_cleaner.locale, Some(Language::English));
+        }
+
+        assert_eq!(
+            token_cleaners
+                .into_iter()
+                .map(|token_cleaner| token_cleaner.map(|value| value.0).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["quick", "brown", "fox"],
+                vec!["quick", "brown", "fox", "jumps", "lazy", "dog", "runs", "farmer"],
+                vec!["lazy", "dog"],
+            ]
+        );
     }
 
-    #[cfg(feature = "tokenizer-japanese")]
     #[test]
-    fn it_cleans_token_japanese_lindera_product() {
-        let mut token_cleaner = TokenLexerBuilder::from(
+    fn it_detects_token_lang_undetermined() {
+        let options = TokenLexerOptions {
+            mark_undetermined: true,
+            ..Default::default()
+        };
+
+        let token_cleaner = TokenLexerBuilder::from_with_options(
             TokenLexerMode::NormalizeAndCleanup(None),
-            "関西国際空港限定トートバッグ",
+            "🚀 🙋‍♂️🙋‍♂️🙋‍♂️",
+            options.clone(),
         )
         .unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
-        assert_eq!(token_cleaner.next(), Some(("関西".to_string(), 1283572620)));
-        assert_eq!(token_cleaner.next(), Some(("国際".to_string(), 2132457693)));
-        assert_eq!(token_cleaner.next(), Some(("空港".to_string(), 865668138)));
-        assert_eq!(token_cleaner.next(), Some(("限定".to_string(), 3708465176)));
+        assert_eq!(token_cleaner.detection(), TokenLexerDetection::Undetermined);
         assert_eq!(
-            token_cleaner.next(),
-            Some(("トート".to_string(), 881444746))
+            token_cleaner.map(|value| value.0).collect::<Vec<_>>(),
+            vec![TOKEN_UNDETERMINED_MARKER]
         );
+
+        for mode in [
+            TokenLexerMode::NormalizeOnly,
+            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+        ] {
+            let token_cleaner =
+                TokenLexerBuilder::from_with_options(mode, "🚀 🙋‍♂️🙋‍♂️🙋‍♂️", options.clone()).unwrap();
+
+            assert_eq!(token_cleaner.detection(), TokenLexerDetection::NotAttempted);
+            assert_eq!(token_cleaner.count(), 0);
+        }
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_token_lang_determined() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "The quick brown fox jumps over the lazy dog!",
+            TokenLexerOptions {
+                mark_undetermined: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
         assert_eq!(
-            token_cleaner.next(),
-            Some(("バッグ".to_string(), 3515727814))
+            token_cleaner.detection(),
+            TokenLexerDetection::Detected(Language::English)
         );
-        assert_eq!(token_cleaner.next(), None);
+        assert!(!token_cleaner
+            .map(|value| value.0)
+            .any(|word| word == TOKEN_UNDETERMINED_MARKER));
     }
 
-    #[cfg(feature = "tokenizer-japanese")]
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_cleans_token_japanese_lindera_food() {
-        let token_cleaner =
-            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "𠮷野家").unwrap();
+    fn it_detects_token_lang_undetected_sink() {
+        let undetected = Arc::new(Mutex::new(Vec::new()));
+        let undetected_inner = undetected.clone();
 
-        assert_eq!(token_cleaner.locale, None);
+        let options = TokenLexerOptions {
+            undetected_sink: Some(Arc::new(move |text: &str| {
+                undetected_inner.lock().unwrap().push(text.to_string())
+            })),
+            ..Default::default()
+        };
 
-        let token_cleaner =
-            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "ヱビスビール")
-                .unwrap();
+        for text in ["🚀 🙋‍♂️🙋‍♂️🙋‍♂️", "The quick brown fox jumps over the lazy dog!"]
+        {
+            TokenLexerBuilder::from_with_options(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                text,
+                options.clone(),
+            )
+            .unwrap();
+        }
 
-        assert_eq!(token_cleaner.locale, None);
+        assert_eq!(*undetected.lock().unwrap(), vec!["🚀 🙋‍♂️🙋‍♂️🙋‍♂️"]);
     }
 
-    #[cfg(feature = "tokenizer-japanese")]
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_cleans_token_japanese_lindera_sentence() {
-        let mut token_cleaner = TokenLexerBuilder::from(
+    fn it_detects_token_lang_cached() {
+        fn assert_shareable<T: Send + Sync>(_: &T) {}
+
+        let cache = TokenLexerDetectionCache::new();
+
+        assert_shareable(&cache);
+
+        let text = "The quick brown fox jumps over the lazy dog!";
+
+        let before = LexerMetrics::thread_snapshot();
+
+        for _ in 0..2 {
+            let token_cleaner = TokenLexerBuilder::from_with_cache(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                text,
+                TokenLexerOptions::default(),
+                &cache,
+            )
+            .unwrap();
+
+            assert_eq!(token_cleaner.locale, Some(Language::English));
+        }
+
+        assert_eq!(
+            LexerMetrics::thread_snapshot().detection_calls,
+            before.detection_calls + 1
+        );
+        assert_eq!(
+            LexerMetrics::thread_snapshot().detection_cache_hits,
+            before.detection_cache_hits + 1
+        );
+
+        // Locales detected among present scripts only are cached apart
+        let token_cleaner = TokenLexerBuilder::from_with_cache(
             TokenLexerMode::NormalizeAndCleanup(None),
-            "𠮷野家でヱビスビールを飲んだ",
+            text,
+            TokenLexerOptions {
+                detect_present_scripts: true,
+                ..Default::default()
+            },
+            &cache,
         )
         .unwrap();
 
-        assert_eq!(token_cleaner.locale, Some(Language::Japanese));
-        assert_eq!(token_cleaner.next(), Some(("𠮷".to_string(), 2866455824)));
-        assert_eq!(token_cleaner.next(), Some(("野家".to_string(), 1324395598)));
+        assert_eq!(token_cleaner.locale, Some(Language::English));
         assert_eq!(
-            token_cleaner.next(),
-            Some(("ヱビス".to_string(), 1696836208))
+            LexerMetrics::thread_snapshot().detection_cache_hits,
+            before.detection_cache_hits + 1
         );
+    }
+
+    #[cfg(feature = "detector-lingua")]
+    #[test]
+    fn it_detects_lang_report() {
+        let report =
+            TokenLexerBuilder::detect_lang_report("The quick brown fox jumps over the lazy dog!");
+
         assert_eq!(
-            token_cleaner.next(),
-            Some(("ビール".to_string(), 3421909800))
+            report.first().map(|(lang, _)| *lang),
+            Some(Language::English)
         );
-        assert_eq!(token_cleaner.next(), Some(("飲ん".to_string(), 3196735184)));
-        assert_eq!(token_cleaner.next(), None);
+        assert!(report
+            .windows(2)
+            .all(|confidences| confidences[0].1 >= confidences[1].1));
     }
 
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_cleans_token_emojis() {
-        let mut token_cleaner =
-            TokenLexerBuilder::from(TokenLexerMode::NormalizeAndCleanup(None), "🚀 🙋‍♂️🙋‍♂️🙋‍♂️")
-                .unwrap();
+    fn it_evaluates_lang_detection() {
+        let report = TokenLexerBuilder::evaluate_detection(
+            &[
+                (
+                    "The quick brown fox jumps over the lazy dog!",
+                    Language::English,
+                ),
+                (
+                    "I believe there is something wrong with this engine.",
+                    Language::English,
+                ),
+                ("12/05/2021 - 14:00", Language::English),
+                (
+                    "Le vif renard brun saute par-dessus le chien paresseux.",
+                    Language::French,
+                ),
+            ],
+            &TokenLexerOptions::default(),
+        );
 
-        assert_eq!(token_cleaner.locale, None);
-        assert_eq!(token_cleaner.next(), None);
+        assert_eq!(report.samples, 4);
+        assert_eq!(report.correct, 3);
+        assert_eq!(report.undetected, 1);
+        assert_eq!(report.accuracy(), 0.75);
+
+        let english = report.languages.get(&Language::English).unwrap();
+
+        assert_eq!(english.precision(), Some(1.0));
+        assert!((english.recall().unwrap() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        let french = report.languages.get(&Language::French).unwrap();
+
+        assert_eq!(french.precision(), Some(1.0));
+        assert_eq!(french.recall(), Some(1.0));
+
+        assert_eq!(
+            TokenLexerBuilder::evaluate_detection(&[], &TokenLexerOptions::default()).accuracy(),
+            0.0
+        );
     }
 
     #[test]
-    fn it_cleans_token_lang_hinted() {
-        let mut token_cleaner_right = TokenLexerBuilder::from(
-            TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
-            "This will be cleaned properly, as English was hinted rightfully so.",
-        )
-        .unwrap();
-        let mut token_cleaner_wrong = TokenLexerBuilder::from(
-            TokenLexerMode::NormalizeAndCleanup(Some(Language::French)),
-            "This will not be cleaned properly, as French was hinted but this is English.",
-        )
-        .unwrap();
+    fn it_detects_lang_candidates() {
+        let candidates = TokenLexerBuilder::detect_lang_candidates("Привет, как у тебя дела?");
 
-        assert_eq!(token_cleaner_right.locale, Some(Language::English));
-        assert_eq!(token_cleaner_wrong.locale, Some(Language::French));
+        assert_eq!(candidates.len(), 8);
+        assert!(candidates.contains(&Language::Russian));
+        assert!(!candidates.contains(&Language::English));
 
         assert_eq!(
-            token_cleaner_right.next(),
-            Some(("cleaned".to_string(), 3550382624))
+            TokenLexerBuilder::detect_lang_candidates("Привет, how are you?").len(),
+            57
         );
         assert_eq!(
-            token_cleaner_wrong.next(),
-            Some(("this".to_string(), 493303710))
+            TokenLexerBuilder::detect_lang_candidates("Γεια σου κόσμε"),
+            vec![Language::Greek]
+        );
+        assert_eq!(
+            TokenLexerBuilder::detect_lang_candidates("東京に行きました"),
+            vec![Language::Chinese, Language::Japanese]
         );
+        assert!(TokenLexerBuilder::detect_lang_candidates("2019-11-05").is_empty());
     }
 
+    #[cfg(feature = "detector-lingua")]
     #[test]
-    fn it_detects_lang_english_regular() {
+    fn it_detects_lang_present_scripts() {
+        let token_cleaner = TokenLexerBuilder::from_with_options(
+            TokenLexerMode::NormalizeAndCleanup(None),
+            "Привет, как у тебя дела сегодня? Я очень рад тебя видеть снова.",
+            TokenLexerOptions {
+                detect_present_scripts: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_cleaner.locale, Some(Language::Russian));
         assert_eq!(
-            TokenLexerBuilder::detect_lang("The quick brown fox jumps over the lazy dog!"),
-            Some(Language::English)
+            TokenLexerBuilder::detect_lang("Γεια σου κόσμε, τι κάνεις σήμερα;", true),
+            Some(Language::Greek)
         );
     }
 
+    #[cfg(feature = "detector-lingua")]
     #[test]
     fn it_detects_lang_english_long() {
         assert_eq!(
@@ -461,16 +8168,18 @@ mod tests {
             the latter of which can be used as a reliable, zero-emission fuel source. In the past,
             the process of purifying water beforehand was too energy intensive for this process to
             be useful — but now scientists have figured out how to skip the process altogether and
-            convert seawater into usable hydrogen"#
+            convert seawater into usable hydrogen"#,
+                false
             ),
             Some(Language::English)
         );
     }
 
+    #[cfg(feature = "detector-lingua")]
     #[test]
     fn it_detects_lang_english_tiny() {
         assert_eq!(
-            TokenLexerBuilder::detect_lang("The quick"),
+            TokenLexerBuilder::detect_lang("The quick", false),
             Some(Language::English)
         );
     }
@@ -506,6 +8215,58 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_normalize_token_french_exhaust_hashes_only(b: &mut Bencher) {
+        b.iter(|| {
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeOnly,
+                "Le vif renard brun saute par dessus le chien paresseux.",
+            )
+            .unwrap();
+
+            token_cleaner.hashes_only().collect::<Vec<u32>>()
+        });
+    }
+
+    #[bench]
+    fn bench_normalize_token_french_upper_exhaust(b: &mut Bencher) {
+        b.iter(|| {
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeOnly,
+                "LE VIF RENARD BRUN SAUTE PAR DESSUS LE CHIEN PARESSEUX.",
+            )
+            .unwrap();
+
+            token_cleaner.map(|value| value.1).collect::<Vec<u32>>()
+        });
+    }
+
+    #[bench]
+    fn bench_normalize_token_french_upper_exhaust_hashes_only(b: &mut Bencher) {
+        b.iter(|| {
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeOnly,
+                "LE VIF RENARD BRUN SAUTE PAR DESSUS LE CHIEN PARESSEUX.",
+            )
+            .unwrap();
+
+            token_cleaner.hashes_only().collect::<Vec<u32>>()
+        });
+    }
+
+    #[bench]
+    fn bench_clean_token_english_single_word_exhaust(b: &mut Bencher) {
+        b.iter(|| {
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
+                "Sonic",
+            )
+            .unwrap();
+
+            token_cleaner.map(|value| value.1).collect::<Vec<u32>>()
+        });
+    }
+
     #[bench]
     fn bench_clean_token_english_regular_build(b: &mut Bencher) {
         b.iter(|| {
@@ -546,11 +8307,28 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_clean_token_english_long_exhaust_arena(b: &mut Bencher) {
+        b.iter(|| {
+            let token_cleaner = TokenLexerBuilder::from(
+                TokenLexerMode::NormalizeAndCleanup(None),
+                r#"Running an electrical current through water splits it into oxygen and hydrogen,
+                the latter of which can be used as a reliable, zero-emission fuel source. In the
+                past, the process of purifying water beforehand was too energy intensive for this
+                process to be useful — but now scientists have figured out how to skip the process
+                altogether and convert seawater into usable hydrogen"#,
+            )
+            .unwrap();
+
+            token_cleaner.collect_arena()
+        });
+    }
+
     #[bench]
     fn bench_clean_token_english_hinted_build(b: &mut Bencher) {
         b.iter(|| {
             TokenLexerBuilder::from(
-                TokenLexerMode::NormalizeAndCleanup(Some(Lang::Eng)),
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
                 "The quick brown fox jumps over the lazy dog!",
             )
         });
@@ -560,7 +8338,7 @@ mod benches {
     fn bench_clean_token_english_hinted_exhaust(b: &mut Bencher) {
         b.iter(|| {
             let token_cleaner = TokenLexerBuilder::from(
-                TokenLexerMode::NormalizeAndCleanup(Some(Lang::Eng)),
+                TokenLexerMode::NormalizeAndCleanup(Some(Language::English)),
                 "The quick brown fox jumps over the lazy dog!",
             )
             .unwrap();
@@ -617,12 +8395,14 @@ mod benches {
 
     #[bench]
     fn bench_detect_lang_english_short(b: &mut Bencher) {
-        b.iter(|| TokenLexerBuilder::detect_lang("The quick brown fox."));
+        b.iter(|| TokenLexerBuilder::detect_lang("The quick brown fox.", false));
     }
 
     #[bench]
     fn bench_detect_lang_english_regular(b: &mut Bencher) {
-        b.iter(|| TokenLexerBuilder::detect_lang("The quick brown fox jumps over the lazy dog!"));
+        b.iter(|| {
+            TokenLexerBuilder::detect_lang("The quick brown fox jumps over the lazy dog!", false)
+        });
     }
 
     #[bench]
@@ -634,12 +8414,13 @@ mod benches {
             the process of purifying water beforehand was too energy intensive for this process to
             be useful — but now scientists have figured out how to skip the process altogether and
             convert seawater into usable hydrogen"#,
+                false,
             )
         });
     }
 
     #[bench]
     fn bench_dont_detect_lang_english_tiny(b: &mut Bencher) {
-        b.iter(|| TokenLexerBuilder::detect_lang("The quick"));
+        b.iter(|| TokenLexerBuilder::detect_lang("The quick", false));
     }
 }
@@ -4,15 +4,32 @@
 // Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-use lingua::{IsoCode639_3, Language};
+use std::fmt;
 use std::str::FromStr;
 
+use crate::lexer::lang::{IsoCode639_3, Language};
+use crate::lexer::stopwords::LexerStopWord;
+
 #[derive(Debug, PartialEq)]
 pub enum QueryGenericLang {
     Enabled(Language),
+    Auto,
     Disabled,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum QueryGenericLangSupport {
+    Generic,
+    Tokenizer,
+    Stopwords,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QueryGenericLangError {
+    NoTokenizer(Language),
+    NoStopwords(Language),
+}
+
 pub type QuerySearchID<'a> = &'a str;
 pub type QuerySearchLimit = u16;
 pub type QuerySearchOffset = u32;
@@ -29,6 +46,8 @@ impl QueryGenericLang {
     pub fn from_value(value: &str) -> Option<QueryGenericLang> {
         if value == "none" {
             Some(QueryGenericLang::Disabled)
+        } else if value == "auto" {
+            Some(QueryGenericLang::Auto)
         } else {
             let _isocode = IsoCode639_3::from_str(value);
             if _isocode.is_err() {
@@ -38,6 +57,55 @@ impl QueryGenericLang {
             Some(QueryGenericLang::Enabled(language))
         }
     }
+
+    pub fn support(&self) -> Result<QueryGenericLangSupport, QueryGenericLangError> {
+        match self {
+            QueryGenericLang::Enabled(lang) => {
+                match lang {
+                    // Languages written with no spaces between words require a dedicated \
+                    //   tokenizer, as stopwords alone would not help in segmenting words.
+                    Language::Chinese => {
+                        if cfg!(feature = "tokenizer-chinese") {
+                            Ok(QueryGenericLangSupport::Tokenizer)
+                        } else {
+                            Err(QueryGenericLangError::NoTokenizer(*lang))
+                        }
+                    }
+                    Language::Japanese => {
+                        if cfg!(feature = "tokenizer-japanese") {
+                            Ok(QueryGenericLangSupport::Tokenizer)
+                        } else {
+                            Err(QueryGenericLangError::NoTokenizer(*lang))
+                        }
+                    }
+                    _ => {
+                        if LexerStopWord::is_supported(*lang) {
+                            Ok(QueryGenericLangSupport::Stopwords)
+                        } else {
+                            Err(QueryGenericLangError::NoStopwords(*lang))
+                        }
+                    }
+                }
+            }
+            QueryGenericLang::Auto | QueryGenericLang::Disabled => {
+                // No language is forced (either auto-detected, or lexing is disabled)
+                Ok(QueryGenericLangSupport::Generic)
+            }
+        }
+    }
+}
+
+impl fmt::Display for QueryGenericLangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            QueryGenericLangError::NoTokenizer(lang) => {
+                write!(f, "unsupported_tokenizer({})", lang.iso_code_639_3())
+            }
+            QueryGenericLangError::NoStopwords(lang) => {
+                write!(f, "unsupported_stopwords({})", lang.iso_code_639_3())
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -50,10 +118,53 @@ mod tests {
             QueryGenericLang::from_value("none"),
             Some(QueryGenericLang::Disabled)
         );
+        assert_eq!(
+            QueryGenericLang::from_value("auto"),
+            Some(QueryGenericLang::Auto)
+        );
         assert_eq!(
             QueryGenericLang::from_value("fra"),
             Some(QueryGenericLang::Enabled(Language::French))
         );
         assert_eq!(QueryGenericLang::from_value("xxx"), None);
     }
+
+    #[test]
+    fn it_checks_generic_lang_support() {
+        assert_eq!(
+            QueryGenericLang::Enabled(Language::English).support(),
+            Ok(QueryGenericLangSupport::Stopwords)
+        );
+        assert_eq!(
+            QueryGenericLang::Auto.support(),
+            Ok(QueryGenericLangSupport::Generic)
+        );
+        assert_eq!(
+            QueryGenericLang::Enabled(Language::Yoruba).support(),
+            Err(QueryGenericLangError::NoStopwords(Language::Yoruba))
+        );
+    }
+
+    #[cfg(feature = "tokenizer-japanese")]
+    #[test]
+    fn it_checks_generic_lang_support_tokenizer() {
+        assert_eq!(
+            QueryGenericLang::Enabled(Language::Japanese).support(),
+            Ok(QueryGenericLangSupport::Tokenizer)
+        );
+    }
+
+    #[cfg(not(feature = "tokenizer-japanese"))]
+    #[test]
+    fn it_checks_generic_lang_support_no_tokenizer() {
+        let error = QueryGenericLang::Enabled(Language::Japanese)
+            .support()
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            QueryGenericLangError::NoTokenizer(Language::Japanese)
+        );
+        assert_eq!(format!("{}", error), "unsupported_tokenizer(jpn)");
+    }
 }
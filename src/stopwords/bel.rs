@@ -4,5 +4,116 @@
 // Copyright: 2019, Valerian Saliou <valerian@valeriansaliou.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-// Notice: we do not have stopwords for this language yet.
-pub static STOPWORDS_BEL: &[&str] = &[];
+// Notice: this list is made of common Belarusian function words (ie. pronouns, prepositions, \
+//   conjunctions and particles).
+pub static STOPWORDS_BEL: &[&str] = &[
+    "а",
+    "аб",
+    "або",
+    "адзін",
+    "але",
+    "бо",
+    "была",
+    "было",
+    "былі",
+    "быць",
+    "быў",
+    "вам",
+    "вас",
+    "ваш",
+    "ваша",
+    "вельмі",
+    "вось",
+    "вы",
+    "гэта",
+    "гэтае",
+    "гэтая",
+    "гэты",
+    "гэтыя",
+    "да",
+    "дзе",
+    "для",
+    "жа",
+    "з",
+    "за",
+    "зноў",
+    "й",
+    "каб",
+    "каля",
+    "калі",
+    "кожны",
+    "куды",
+    "ля",
+    "мае",
+    "мая",
+    "маё",
+    "мне",
+    "мой",
+    "мы",
+    "мяне",
+    "на",
+    "нават",
+    "над",
+    "нам",
+    "нас",
+    "наш",
+    "не",
+    "ну",
+    "ні",
+    "нібы",
+    "па",
+    "пад",
+    "паміж",
+    "пасля",
+    "потым",
+    "пра",
+    "праз",
+    "пры",
+    "сабе",
+    "сам",
+    "свой",
+    "сябе",
+    "табе",
+    "так",
+    "таксама",
+    "такі",
+    "там",
+    "тая",
+    "тое",
+    "той",
+    "толькі",
+    "тут",
+    "ты",
+    "у",
+    "усе",
+    "усё",
+    "усіх",
+    "хоць",
+    "хто",
+    "цябе",
+    "цяпер",
+    "ці",
+    "чаго",
+    "чаму",
+    "чым",
+    "што",
+    "я",
+    "яго",
+    "яе",
+    "як",
+    "якая",
+    "якое",
+    "які",
+    "якія",
+    "яна",
+    "яно",
+    "яны",
+    "ёй",
+    "ён",
+    "ёсць",
+    "і",
+    "ім",
+    "імі",
+    "іх",
+    "ў",
+];
@@ -416,6 +416,7 @@ pub static STOPWORDS_CMN: &[&str] = &[
     "己",
     "已",
     "已矣",
+    "已经",
     "巴",
     "巴巴",
     "年",
@@ -549,7 +550,9 @@ pub static STOPWORDS_CMN: &[&str] = &[
     "比及",
     "比如",
     "比方",
+    "没",
     "没奈何",
+    "没有",
     "沿",
     "沿着",
     "漫说",
@@ -30,6 +30,8 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "から",
     "が",
     "き",
+    "けど",
+    "けれど",
     "ここ",
     "こちら",
     "こと",
@@ -38,8 +40,10 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "これら",
     "さ",
     "さらに",
+    "され",
     "し",
     "しかし",
+    "じゃ",
     "する",
     "ず",
     "せ",
@@ -60,11 +64,14 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "だ",
     "だっ",
     "だれ",
+    "って",
     "つ",
     "て",
     "で",
     "でき",
     "できる",
+    "でし",
+    "でしょ",
     "です",
     "では",
     "でも",
@@ -103,6 +110,7 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "に対して",
     "に対する",
     "に関する",
+    "ね",
     "の",
     "ので",
     "のみ",
@@ -112,7 +120,9 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "ほか",
     "ほとんど",
     "ほど",
+    "まし",
     "ます",
+    "ませ",
     "また",
     "または",
     "まで",
@@ -120,7 +130,9 @@ pub static STOPWORDS_JPN: &[&str] = &[
     "もの",
     "ものの",
     "や",
+    "よ",
     "よう",
+    "ような",
     "より",
     "ら",
     "られ",
@@ -31,12 +31,30 @@ impl StoreMetaKey {
 
 impl StoreTermHash {
     pub fn from(term: &str) -> StoreTermHashed {
+        // Notice: term hashes are stored in the index, thus they must be the same on all \
+        //   platforms. This holds as the hashed bytes are UTF-8 (which has no byte order), and \
+        //   as xxHash32 reads its input in little-endian order on all platforms. Do not hash \
+        //   the term through 'Hash::hash()', as this appends a terminator byte to the input.
         let mut hasher = XxHash32::with_seed(0);
 
         hasher.write(term.as_bytes());
 
         hasher.finish() as u32
     }
+
+    pub fn from_chars<I: Iterator<Item = char>>(chars: I) -> StoreTermHashed {
+        // Hash a term from its characters, without collecting them into a string first
+        // Notice: this yields the same hash as 'from()' does on the collected string, as the \
+        //   hasher gets written the very same UTF-8 bytes.
+        let mut hasher = XxHash32::with_seed(0);
+        let mut buffer = [0; 4];
+
+        for character in chars {
+            hasher.write(character.encode_utf8(&mut buffer).as_bytes());
+        }
+
+        hasher.finish() as u32
+    }
 }
 
 #[cfg(test)]
@@ -53,4 +71,32 @@ mod tests {
         assert_eq!(StoreTermHash::from("hash:1"), 3637660813);
         assert_eq!(StoreTermHash::from("hash:2"), 3577985381);
     }
+
+    #[test]
+    fn it_hashes_term_chars() {
+        for term in [
+            "",
+            "hash:1",
+            "Nobody inspects the spammish repetition",
+            "𠮷野家",
+        ] {
+            assert_eq!(
+                StoreTermHash::from_chars(term.chars()),
+                StoreTermHash::from(term)
+            );
+        }
+    }
+
+    #[test]
+    fn it_hashes_term_platform_independent() {
+        // Reference xxHash32 values (seed 0), which must be the same on all platforms \
+        //   (including big-endian ones)
+        assert_eq!(StoreTermHash::from(""), 0x02CC5D05);
+        assert_eq!(StoreTermHash::from("abc"), 0x32D153FF);
+        assert_eq!(
+            StoreTermHash::from("Nobody inspects the spammish repetition"),
+            0xE2293B2F
+        );
+        assert_eq!(StoreTermHash::from("𠮷野家"), 0xC0784E1F);
+    }
 }